@@ -0,0 +1,811 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::config::AuthMode;
+use crate::error::{check, AppError};
+use crate::import::Entwurf;
+use crate::{keycloak, Antrag, AntragStatus, Deleted, ListResponse, Sitzung, Top};
+
+/// Work for the network task. Every request is answered by exactly one
+/// final `Response`, imports additionally report `ImportProgress` on the way.
+pub enum Request {
+    Sitzungen,
+    Tops(Uuid),
+    Anträge(Uuid),
+    AntragCount(Uuid),
+    Sitzung(Uuid),
+    Top(Uuid),
+    Antrag(Uuid),
+    Send(Mutation),
+    /// Like `Send`, but reads the id of the created object from the response.
+    Create(Mutation),
+    /// An autosave of the notes of a Top, answered with `NotesSaved`.
+    SaveNotes(Mutation),
+    /// Lists soft-deleted items from the given `?deleted=true` URL.
+    Trash(String),
+    /// Runs the Keycloak login again.
+    Login,
+    /// Asks the backend which version it runs, answered with `Version`.
+    Version,
+    /// Fetches a single item as the backend sent it, answered with `Raw`.
+    Raw(String),
+    /// All Tops of a Sitzung in agenda order, each with its Anträge.
+    Agenda(Uuid),
+    /// Counts the Tops and Anträge of the given Sitzungen.
+    Stats(Vec<Uuid>),
+    /// Moves every Top of `source` with its Anträge to the end of the
+    /// agenda of `target`, answered with `Merged`.
+    Merge {
+        source: Uuid,
+        target: Uuid,
+        token: String,
+    },
+    /// Sets the weight of each listed Top of the Sitzung, one PATCH each.
+    Renumber {
+        sitzung_id: Uuid,
+        token: String,
+        weights: Vec<(Uuid, i32)>,
+    },
+    /// Creates a Top and then the given Anträge under it.
+    CreateTop {
+        sitzung_id: Uuid,
+        token: String,
+        top: serde_json::Value,
+        anträge: Vec<serde_json::Value>,
+    },
+    Import {
+        url: String,
+        token: String,
+        entwürfe: Vec<Entwurf>,
+        /// Once set, no further Anträge are sent.
+        cancel: Arc<AtomicBool>,
+    },
+}
+
+#[derive(Clone)]
+pub struct Mutation {
+    pub method: Method,
+    pub url: String,
+    pub body: Option<serde_json::Value>,
+    pub token: String,
+    /// What to fetch again once the mutation went through.
+    pub reload: Reload,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Reload {
+    Sitzungen,
+    Tops,
+    Anträge,
+    All,
+}
+
+pub enum Response {
+    Sitzungen(Result<Vec<Sitzung>, AppError>),
+    Tops(Uuid, Result<Vec<Top>, AppError>),
+    Anträge(Uuid, Result<Vec<Antrag>, AppError>),
+    AntragCount(Uuid, Option<AntragCount>),
+    Sitzung(Result<Sitzung, AppError>),
+    Top(Result<Top, AppError>),
+    Antrag(Result<Antrag, AppError>),
+    /// The mutation comes back so a failed one can be sent again.
+    Sent(Mutation, Result<(), AppError>),
+    Created(Mutation, Result<Uuid, AppError>),
+    NotesSaved(Result<(), AppError>),
+    Stats(Result<Stats, AppError>),
+    Agenda(Uuid, Result<Agenda, AppError>),
+    Trash(String, Result<Vec<Deleted>, AppError>),
+    Login(anyhow::Result<keycloak::Token>),
+    /// `None` if the backend does not tell its version.
+    Version(Option<String>),
+    Raw(String, Result<Value, AppError>),
+    TopCreated {
+        top: Result<Uuid, AppError>,
+        anträge: Vec<Result<(), String>>,
+    },
+    /// Sent alongside a list response when some of its items could not be
+    /// read and were left out.
+    Malformed {
+        url: String,
+        count: usize,
+    },
+    ImportProgress {
+        done: usize,
+        total: usize,
+        titel: String,
+    },
+    RenumberProgress {
+        done: usize,
+        total: usize,
+    },
+    /// One entry per Top that was to be renumbered.
+    Renumbered(Vec<Result<(), String>>),
+    MergeProgress {
+        done: usize,
+        total: usize,
+        titel: String,
+    },
+    Merged(Result<MergeReport, AppError>),
+    /// One entry per processed block, `Ok` holds the title of the created
+    /// Antrag. Shorter than the input if the import was cancelled.
+    Imported(Vec<Result<String, String>>),
+}
+
+/// How moving the Tops of one Sitzung into another went.
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    pub total: usize,
+    pub moved: usize,
+    /// One line per Top left in the source, with why.
+    pub failures: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub tops: usize,
+    pub anträge: usize,
+    /// Anträge that are still `eingereicht`.
+    pub open: usize,
+    /// One line per Top left out of the counts, with why.
+    pub failed: Vec<String>,
+}
+
+/// The Tops of a Sitzung in agenda order with their Anträge.
+#[derive(Debug, Clone, Default)]
+pub struct Agenda {
+    pub tops: Vec<(Top, Vec<Antrag>)>,
+    /// One line per Top left out because its Anträge could not be loaded,
+    /// with why.
+    pub failed: Vec<String>,
+}
+
+impl Agenda {
+    /// See [`partial`].
+    pub fn partial(&self, verb: &str) -> Option<String> {
+        partial(verb, self.tops.len(), self.failed.len())
+    }
+}
+
+/// How much of a whole-Sitzung operation went through, e.g. `Exported
+/// 9/10 Tops (1 failed)`. `None` if no Top failed.
+pub fn partial(verb: &str, done: usize, failed: usize) -> Option<String> {
+    (failed > 0).then(|| {
+        format!(
+            "{} {}/{} Tops ({} failed)",
+            verb,
+            done,
+            done + failed,
+            failed
+        )
+    })
+}
+
+/// The Anträge of one Top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AntragCount {
+    pub total: usize,
+    /// Anträge that are still `eingereicht`.
+    pub open: usize,
+}
+
+impl AntragCount {
+    pub fn of(anträge: &[Antrag]) -> Self {
+        AntragCount {
+            total: anträge.len(),
+            open: anträge
+                .iter()
+                .filter(|antrag| antrag.status == AntragStatus::Eingereicht)
+                .count(),
+        }
+    }
+}
+
+/// Handle to the task that runs all HTTP requests, so the render loop never
+/// waits on the network. Responses are picked up with `try_recv`.
+pub struct Backend {
+    requests: UnboundedSender<(Request, Option<oneshot::Receiver<()>>)>,
+    responses: UnboundedReceiver<Response>,
+}
+
+/// Aborts a request sent with `send_cancellable`. Dropping it does not.
+pub struct Cancel(oneshot::Sender<()>);
+
+impl Cancel {
+    /// Drops the request together with its connection, it gets no response.
+    pub fn cancel(self) {
+        let _ = self.0.send(());
+    }
+}
+
+impl Backend {
+    /// With `insecure` the client accepts invalid TLS certificates, e.g. a
+    /// self-signed one on a local backend. `auth` decides how tokens are sent.
+    /// All requests go to `base`, also those still running after the app
+    /// switched to another backend.
+    pub fn spawn(base: String, insecure: bool, auth: AuthMode) -> Self {
+        let (requests, mut request_rx) =
+            mpsc::unbounded_channel::<(Request, Option<oneshot::Receiver<()>>)>();
+        let (response_tx, responses) = mpsc::unbounded_channel();
+        let client = client(insecure);
+        tokio::spawn(async move {
+            while let Some((request, cancel)) = request_rx.recv().await {
+                let client = client.clone();
+                let base = base.clone();
+                let auth = auth.clone();
+                let response_tx = response_tx.clone();
+                tokio::spawn(async move {
+                    let handled = handle(&client, &base, &auth, request, &response_tx);
+                    let response = match cancel {
+                        Some(cancel) => tokio::select! {
+                            response = handled => response,
+                            Ok(()) = cancel => return,
+                        },
+                        None => handled.await,
+                    };
+                    let _ = response_tx.send(response);
+                });
+            }
+        });
+        Backend {
+            requests,
+            responses,
+        }
+    }
+
+    pub fn send(&self, request: Request) {
+        let _ = self.requests.send((request, None));
+    }
+
+    pub fn send_cancellable(&self, request: Request) -> Cancel {
+        let (cancel, cancelled) = oneshot::channel();
+        let _ = self.requests.send((request, Some(cancelled)));
+        Cancel(cancel)
+    }
+
+    pub fn try_recv(&mut self) -> Option<Response> {
+        self.responses.try_recv().ok()
+    }
+}
+
+/// How long a single request may take before it fails with `Timeout`.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// With `insecure` the client accepts invalid TLS certificates.
+pub fn client(insecure: bool) -> Client {
+    Client::builder()
+        .danger_accept_invalid_certs(insecure)
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+async fn handle(
+    client: &Client,
+    base: &str,
+    auth: &AuthMode,
+    request: Request,
+    events: &UnboundedSender<Response>,
+) -> Response {
+    let reader = Reader {
+        client,
+        base,
+        auth: None,
+    };
+    match request {
+        Request::Sitzungen => {
+            let url = format!("{}api/topmanager/sitzungen/", base);
+            Response::Sitzungen(fetch_list(reader, url, events).await)
+        }
+        Request::Tops(id) => Response::Tops(id, fetch_tops(reader, id, events).await),
+        Request::Anträge(id) => Response::Anträge(id, fetch_anträge(reader, id, events).await),
+        Request::AntragCount(id) => {
+            let count = fetch_anträge(reader, id, events)
+                .await
+                .ok()
+                .map(|anträge| AntragCount::of(&anträge));
+            Response::AntragCount(id, count)
+        }
+        Request::Sitzung(id) => {
+            let url = format!("{}api/topmanager/sitzung/{}/", base, id);
+            Response::Sitzung(fetch(reader, url).await)
+        }
+        Request::Top(id) => {
+            let url = format!("{}api/topmanager/tops/{}/", base, id);
+            Response::Top(fetch(reader, url).await)
+        }
+        Request::Antrag(id) => {
+            let url = format!("{}api/topmanager/antrag/{}/", base, id);
+            Response::Antrag(fetch(reader, url).await)
+        }
+        Request::Send(mutation) => {
+            let result = send(mutation_request(client, auth, &mutation)).await;
+            Response::Sent(mutation, result)
+        }
+        Request::SaveNotes(mutation) => {
+            Response::NotesSaved(send(mutation_request(client, auth, &mutation)).await)
+        }
+        Request::Create(mutation) => {
+            let result = create(mutation_request(client, auth, &mutation)).await;
+            Response::Created(mutation, result)
+        }
+        Request::Trash(url) => {
+            let deleted = fetch_list(reader, url.clone(), events).await;
+            Response::Trash(url, deleted)
+        }
+        Request::Login => {
+            Response::Login(keycloak::login(keycloak::LOGIN_TIMEOUT, std::future::pending()).await)
+        }
+        Request::Version => Response::Version(fetch_version(reader).await),
+        Request::Raw(url) => Response::Raw(url.clone(), fetch(reader, url).await),
+        Request::CreateTop {
+            sitzung_id,
+            token,
+            top,
+            anträge,
+        } => {
+            let url = format!("{}api/topmanager/sitzung/{}/top/", base, sitzung_id);
+            let request = edit_request(client.put(url), auth, &token, &top);
+            let id = match create(request).await {
+                Ok(id) => id,
+                Err(err) => {
+                    return Response::TopCreated {
+                        top: Err(err),
+                        anträge: vec![],
+                    }
+                }
+            };
+            let url = format!("{}api/topmanager/top/{}/antrag/", base, id);
+            let mut results = vec![];
+            for antrag in anträge {
+                let request = edit_request(client.put(&url), auth, &token, &antrag);
+                results.push(send(request).await.map_err(|err| err.to_string()));
+            }
+            Response::TopCreated {
+                top: Ok(id),
+                anträge: results,
+            }
+        }
+        Request::Agenda(id) => Response::Agenda(id, fetch_agenda(reader, id, events).await),
+        Request::Merge {
+            source,
+            target,
+            token,
+        } => {
+            let sender = Sender {
+                client,
+                base,
+                auth,
+                token: &token,
+            };
+            Response::Merged(merge(&sender, source, target, events).await)
+        }
+        Request::Stats(sitzungen) => Response::Stats(fetch_stats(reader, sitzungen, events).await),
+        Request::Renumber {
+            sitzung_id,
+            token,
+            weights,
+        } => {
+            let url = format!("{}api/topmanager/top/", base);
+            let total = weights.len();
+            let mut results = vec![];
+            for (done, (id, weight)) in weights.into_iter().enumerate() {
+                let _ = events.send(Response::RenumberProgress { done, total });
+                let json = serde_json::json!({
+                    "id": id,
+                    "sitzung_id": sitzung_id,
+                    "weight": weight,
+                });
+                let request = edit_request(client.patch(&url), auth, &token, &json);
+                results.push(send(request).await.map_err(|err| err.to_string()));
+            }
+            Response::Renumbered(results)
+        }
+        Request::Import {
+            url,
+            token,
+            entwürfe,
+            cancel,
+        } => {
+            let total = entwürfe.len();
+            let mut results = vec![];
+            for (index, entwurf) in entwürfe.into_iter().enumerate() {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let _ = events.send(Response::ImportProgress {
+                    done: index,
+                    total,
+                    titel: entwurf.titel.clone(),
+                });
+                let json = serde_json::json!({
+                    "titel": entwurf.titel,
+                    "begründung": entwurf.begründung,
+                    "antragstext": entwurf.antragstext,
+                });
+                let request = edit_request(client.put(&url), auth, &token, &json);
+                results.push(match send(request).await {
+                    Ok(()) => Ok(entwurf.titel),
+                    Err(err) => Err(format!("{}: {}", entwurf.titel, err)),
+                });
+            }
+            Response::Imported(results)
+        }
+    }
+}
+
+/// Where reads go, with the auth mode and token for a backend that
+/// protects them. The TUI only reads with one while merging.
+#[derive(Clone, Copy)]
+pub struct Reader<'a> {
+    pub client: &'a Client,
+    pub base: &'a str,
+    pub auth: Option<(&'a AuthMode, &'a str)>,
+}
+
+impl Reader<'_> {
+    fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+        match self.auth {
+            Some((auth, token)) => apply_auth(request, auth, token),
+            None => request,
+        }
+    }
+}
+
+/// What a sequence of authenticated mutations needs.
+struct Sender<'a> {
+    client: &'a Client,
+    base: &'a str,
+    auth: &'a AuthMode,
+    token: &'a str,
+}
+
+impl Sender<'_> {
+    fn request(&self, method: Method, url: String, body: &Value) -> RequestBuilder {
+        let request = self.client.request(method.clone(), url);
+        match method {
+            Method::PUT | Method::PATCH => edit_request(request, self.auth, self.token, body),
+            _ => apply_auth(request, self.auth, self.token).json(body),
+        }
+    }
+}
+
+/// Copies the Tops of `source` one by one to the end of `target` and
+/// deletes each original once its copy is complete. A copy that fails
+/// halfway is deleted again, leaving that Top in `source`.
+async fn merge(
+    sender: &Sender<'_>,
+    source: Uuid,
+    target: Uuid,
+    events: &UnboundedSender<Response>,
+) -> Result<MergeReport, AppError> {
+    let reader = Reader {
+        client: sender.client,
+        base: sender.base,
+        auth: Some((sender.auth, sender.token)),
+    };
+    let agenda = fetch_agenda(reader, source, events).await?;
+    let existing = fetch_tops(reader, target, events).await?;
+    let weights = appended_weights(&existing, agenda.tops.len());
+    // Tops whose Anträge could not be read stay in `source`, moving them
+    // would lose the Anträge.
+    let mut report = MergeReport {
+        total: agenda.tops.len() + agenda.failed.len(),
+        failures: agenda.failed,
+        ..MergeReport::default()
+    };
+    let total = agenda.tops.len();
+    for (done, ((top, anträge), weight)) in agenda.tops.into_iter().zip(weights).enumerate() {
+        let _ = events.send(Response::MergeProgress {
+            done,
+            total,
+            titel: top.name.clone(),
+        });
+        match move_top(sender, target, &top, &anträge, weight).await {
+            Ok(()) => report.moved += 1,
+            Err(err) => report.failures.push(format!("{}: {}", top.name, err)),
+        }
+    }
+    Ok(report)
+}
+
+async fn move_top(
+    sender: &Sender<'_>,
+    target: Uuid,
+    top: &Top,
+    anträge: &[Antrag],
+    weight: i32,
+) -> Result<(), String> {
+    let url = format!("{}api/topmanager/sitzung/{}/top/", sender.base, target);
+    let json = serde_json::json!({
+        "titel": top.name,
+        "inhalt": top.inhalt,
+        "verantwortlich": top.verantwortlich,
+        "weight": weight,
+    });
+    let copy = create(sender.request(Method::PUT, url, &json))
+        .await
+        .map_err(|err| err.to_string())?;
+    let top_url = format!("{}api/topmanager/top/", sender.base);
+    let mut result = copy_top_contents(sender, target, copy, top, anträge).await;
+    if result.is_ok() {
+        let json = serde_json::json!({ "id": top.id });
+        result = send(sender.request(Method::DELETE, top_url.clone(), &json))
+            .await
+            .map_err(|err| format!("removing the original failed ({})", err));
+    }
+    let Err(err) = result else {
+        return Ok(());
+    };
+    let json = serde_json::json!({ "id": copy });
+    match send(sender.request(Method::DELETE, top_url, &json)).await {
+        Ok(()) => Err(format!("{}, left in place", err)),
+        Err(undo) => Err(format!(
+            "{}, and its copy could not be removed ({})",
+            err, undo
+        )),
+    }
+}
+
+/// The Protokoll and Anträge of `top` for its copy `copy` under `target`.
+async fn copy_top_contents(
+    sender: &Sender<'_>,
+    target: Uuid,
+    copy: Uuid,
+    top: &Top,
+    anträge: &[Antrag],
+) -> Result<(), String> {
+    if !top.protokoll.is_empty() {
+        let url = format!("{}api/topmanager/top/", sender.base);
+        let json = serde_json::json!({
+            "id": copy,
+            "sitzung_id": target,
+            "protokoll": top.protokoll,
+        });
+        send(sender.request(Method::PATCH, url, &json))
+            .await
+            .map_err(|err| format!("copying the Protokoll failed ({})", err))?;
+    }
+    let url = format!("{}api/topmanager/top/{}/antrag/", sender.base, copy);
+    for antrag in anträge {
+        let json = serde_json::json!({
+            "titel": antrag.titel,
+            "begründung": antrag.begründung,
+            "antragstext": antrag.antragstext,
+            "anhänge": antrag.anhänge,
+            "typ": antrag.typ,
+            "antragssteller": antrag.author,
+        });
+        let failed = |err: AppError| format!("copying \"{}\" failed ({})", antrag.titel, err);
+        let id = create(sender.request(Method::PUT, url.clone(), &json))
+            .await
+            .map_err(failed)?;
+        if antrag.status != AntragStatus::default() {
+            let moved = Antrag {
+                id,
+                top_id: Some(copy),
+                created_at: None,
+                updated_at: None,
+                ..antrag.clone()
+            };
+            let status_url = format!("{}api/topmanager/antrag/", sender.base);
+            send(sender.request(Method::PATCH, status_url, &serde_json::json!(moved)))
+                .await
+                .map_err(failed)?;
+        }
+    }
+    Ok(())
+}
+
+/// Weights that put `count` Tops after the existing ones, in order.
+fn appended_weights(existing: &[Top], count: usize) -> Vec<i32> {
+    let first = existing.iter().map(|top| top.weight + 1).max().unwrap_or(0);
+    (first..).take(count).collect()
+}
+
+/// Attaches the access token the way the configured backend expects it.
+fn apply_auth(request: RequestBuilder, auth: &AuthMode, token: &str) -> RequestBuilder {
+    match auth {
+        AuthMode::Cookie => request.header("Cookie", format!("access_token={}", token)),
+        AuthMode::Bearer => request.bearer_auth(token),
+        AuthMode::Header(name) => request.header(name.as_str(), token),
+    }
+}
+
+fn mutation_request(client: &Client, auth: &AuthMode, mutation: &Mutation) -> RequestBuilder {
+    let request = apply_auth(
+        client.request(mutation.method.clone(), &mutation.url),
+        auth,
+        &mutation.token,
+    );
+    match &mutation.body {
+        Some(body) if matches!(mutation.method, Method::PUT | Method::PATCH) => {
+            request.json(&with_editor(body, &mutation.token))
+        }
+        Some(body) => request.json(body),
+        None => request,
+    }
+}
+
+/// A PUT or PATCH of `body`, which records who made the change.
+fn edit_request(
+    request: RequestBuilder,
+    auth: &AuthMode,
+    token: &str,
+    body: &Value,
+) -> RequestBuilder {
+    apply_auth(request, auth, token).json(&with_editor(body, token))
+}
+
+/// `body` with `modified_by` set to the user `token` belongs to, as far
+/// as the token tells.
+fn with_editor(body: &Value, token: &str) -> Value {
+    let mut body = body.clone();
+    if let (Value::Object(fields), Some(user)) = (&mut body, keycloak::user(token)) {
+        fields.insert("modified_by".to_string(), Value::String(user));
+    }
+    body
+}
+
+async fn send(request: RequestBuilder) -> Result<(), AppError> {
+    check(request.send().await?).await?;
+    Ok(())
+}
+
+/// Sends a create request and reads the id of the new object from the
+/// response body.
+async fn create(request: RequestBuilder) -> Result<Uuid, AppError> {
+    let response = check(request.send().await?).await?;
+    let created: serde_json::Value = response.json().await?;
+    created["id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| AppError::Deserialize("response contains no id".to_string()))
+}
+
+pub async fn fetch<T: DeserializeOwned>(reader: Reader<'_>, url: String) -> Result<T, AppError> {
+    Ok(check(reader.get(&url).send().await?).await?.json().await?)
+}
+
+/// Items that fail to deserialize are left out and reported as `Malformed`
+/// instead of failing the whole list.
+async fn fetch_list<T: DeserializeOwned>(
+    reader: Reader<'_>,
+    url: String,
+    events: &UnboundedSender<Response>,
+) -> Result<Vec<T>, AppError> {
+    let response: ListResponse<Value> = fetch(reader, url.clone()).await?;
+    Ok(parse_list(url, response, events))
+}
+
+fn parse_list<T: DeserializeOwned>(
+    url: String,
+    response: ListResponse<Value>,
+    events: &UnboundedSender<Response>,
+) -> Vec<T> {
+    let (items, count) = response.parse_items();
+    if count > 0 {
+        let _ = events.send(Response::Malformed { url, count });
+    }
+    items
+}
+
+/// Only fails if the Tops cannot be listed, a Top whose Anträge fail to
+/// load is left out and listed in `failed`.
+pub async fn fetch_agenda(
+    reader: Reader<'_>,
+    sitzung_id: Uuid,
+    events: &UnboundedSender<Response>,
+) -> Result<Agenda, AppError> {
+    let mut tops = fetch_tops(reader, sitzung_id, events).await?;
+    tops.sort_by_key(|top| top.weight);
+    let mut agenda = Agenda::default();
+    for top in tops {
+        match fetch_anträge(reader, top.id, events).await {
+            Ok(anträge) => agenda.tops.push((top, anträge)),
+            Err(err) => agenda.failed.push(format!("{}: {}", top.name, err)),
+        }
+    }
+    Ok(agenda)
+}
+
+/// Older backends have no version endpoint, any failure counts as unknown.
+async fn fetch_version(reader: Reader<'_>) -> Option<String> {
+    let url = format!("{}api/version", reader.base);
+    let response = reader
+        .get(&url)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?;
+    match response.json().await.ok()? {
+        Value::String(version) => Some(version),
+        Value::Object(object) => object.get("version")?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+async fn fetch_stats(
+    reader: Reader<'_>,
+    sitzungen: Vec<Uuid>,
+    events: &UnboundedSender<Response>,
+) -> Result<Stats, AppError> {
+    let mut stats = Stats::default();
+    for sitzung in sitzungen {
+        for top in fetch_tops(reader, sitzung, events).await? {
+            let anträge = match fetch_anträge(reader, top.id, events).await {
+                Ok(anträge) => anträge,
+                Err(err) => {
+                    stats.failed.push(format!("{}: {}", top.name, err));
+                    continue;
+                }
+            };
+            stats.tops += 1;
+            stats.anträge += anträge.len();
+            stats.open += anträge
+                .iter()
+                .filter(|antrag| antrag.status == AntragStatus::Eingereicht)
+                .count();
+        }
+    }
+    Ok(stats)
+}
+
+pub async fn fetch_tops(
+    reader: Reader<'_>,
+    sitzung_id: Uuid,
+    events: &UnboundedSender<Response>,
+) -> Result<Vec<Top>, AppError> {
+    let url = format!("{}api/topmanager/sitzung/{}/tops/", reader.base, sitzung_id);
+    fetch_list(reader, url, events).await
+}
+
+pub async fn fetch_anträge(
+    reader: Reader<'_>,
+    top_id: Uuid,
+    events: &UnboundedSender<Response>,
+) -> Result<Vec<Antrag>, AppError> {
+    let url = format!("{}api/topmanager/tops/{}/anträge/", reader.base, top_id);
+    let response = reader.get(&url).send().await?;
+    // Some backends answer 404 for a Top without Anträge instead of an
+    // empty list.
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(vec![]);
+    }
+    let response = check(response).await?.json().await?;
+    Ok(parse_list(url, response, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_tops_go_after_the_existing_ones() {
+        let top = |weight| Top {
+            weight,
+            ..Top::default()
+        };
+        assert_eq!(appended_weights(&[top(3), top(0), top(7)], 2), [8, 9]);
+        assert_eq!(appended_weights(&[], 3), [0, 1, 2]);
+        assert_eq!(appended_weights(&[top(-2)], 1), [-1]);
+    }
+
+    #[test]
+    fn partial_results_count_the_failed_tops() {
+        assert_eq!(
+            partial("Exported", 9, 1).as_deref(),
+            Some("Exported 9/10 Tops (1 failed)")
+        );
+        assert_eq!(partial("Exported", 10, 0), None);
+    }
+}
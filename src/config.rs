@@ -0,0 +1,335 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::{palette::tailwind, Color};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Lang;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Config {
+    pub theme: Theme,
+    /// Maps a pressed key to the key it should act as, e.g. `"l" = "o"`.
+    pub keys: HashMap<String, String>,
+    /// Wrap long names in lists instead of cutting them off.
+    pub wrap_names: bool,
+    /// Start with one-line header and footer and one row per item.
+    pub compact: bool,
+    /// Open the dashboard on startup instead of the Sitzungen list.
+    pub dashboard: bool,
+    /// Web page of a Sitzung copied by `L`, `{base}` is the backend URL and
+    /// `{id}` the Sitzung. Defaults to `{base}topmanager/sitzung/{id}/`.
+    pub agenda_url: Option<String>,
+    /// Other backends to switch to with `Ctrl+W`, by name. `url`,
+    /// `insecure` and `auth` above make up the default one.
+    pub profiles: BTreeMap<String, Profile>,
+    /// Select the whole value when opening a field, so typing replaces it.
+    pub select_on_edit: bool,
+    /// Base URL of the backend, defaults to the production instance.
+    pub url: Option<String>,
+    /// Accept invalid TLS certificates. Refused for the production backend.
+    pub insecure: bool,
+    /// Badge colors for Antrag types, e.g. `Finanzantrag = "Green"`.
+    pub typ_colors: HashMap<String, Color>,
+    /// How the access token is sent: `"cookie"`, `"bearer"` or
+    /// `"header:<name>"`. Only read at startup.
+    pub auth: AuthMode,
+    /// Minimum time between keys that load from the backend, keys pressed
+    /// faster are dropped. Defaults to 300.
+    pub request_interval_ms: Option<u64>,
+    /// Reload the current list every this many seconds, except while
+    /// editing. 0 or unset turns it off.
+    pub auto_refresh_secs: Option<u64>,
+    /// Saving an edit opened longer ago than this first offers to fetch
+    /// the item again, for backends that send no `updated_at`. Defaults to 10.
+    pub stale_edit_minutes: Option<u64>,
+    /// The roster the attendance of a Sitzung is taken from.
+    pub members: Vec<String>,
+    /// Text new Anträge start with as their Antragstext. `{sitzung}`,
+    /// `{datum}` and `{top}` are replaced by the current Sitzung and Top.
+    pub antrag_template: Option<String>,
+    /// First line of Anträge exported as PDF, e.g. the name of the body.
+    pub pdf_header: Option<String>,
+    /// Language of titles, hints and messages, `"en"` or `"de"`. Ctrl+G
+    /// switches while running.
+    pub lang: Lang,
+    /// Columns of the Anträge table, toggled with `T`.
+    pub antrag_table: AntragTable,
+}
+
+impl Config {
+    pub fn request_interval(&self) -> Duration {
+        Duration::from_millis(self.request_interval_ms.unwrap_or(300))
+    }
+
+    pub fn auto_refresh(&self) -> Option<Duration> {
+        self.auto_refresh_secs
+            .filter(|&secs| secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    pub fn stale_edit_after(&self) -> Duration {
+        Duration::from_secs(60 * self.stale_edit_minutes.unwrap_or(10))
+    }
+
+    /// The named profile, or the default one made of the top-level settings.
+    pub fn profile(&self, name: Option<&str>) -> Option<Profile> {
+        match name {
+            Some(name) => self.profiles.get(name).cloned(),
+            None => Some(Profile {
+                url: self.url.clone(),
+                insecure: self.insecure,
+                auth: self.auth.clone(),
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Profile {
+    pub url: Option<String>,
+    pub insecure: bool,
+    pub auth: AuthMode,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub enum AuthMode {
+    /// `Cookie: access_token=<token>`, what the production backend expects.
+    #[default]
+    Cookie,
+    /// `Authorization: Bearer <token>`.
+    Bearer,
+    /// The bare token in a header of the given name, e.g. an API key.
+    Header(String),
+}
+
+impl TryFrom<String> for AuthMode {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        match text.as_str() {
+            "cookie" => Ok(AuthMode::Cookie),
+            "bearer" => Ok(AuthMode::Bearer),
+            _ => match text.strip_prefix("header:") {
+                Some(name) if !name.trim().is_empty() => {
+                    Ok(AuthMode::Header(name.trim().to_string()))
+                }
+                _ => Err(format!(
+                    "unknown auth mode '{}', expected cookie, bearer or header:<name>",
+                    text
+                )),
+            },
+        }
+    }
+}
+
+impl From<AuthMode> for String {
+    fn from(auth: AuthMode) -> Self {
+        match auth {
+            AuthMode::Cookie => "cookie".to_string(),
+            AuthMode::Bearer => "bearer".to_string(),
+            AuthMode::Header(name) => format!("header:{}", name),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AntragTable {
+    /// Columns left out, e.g. `["erstellt"]`. The Titel is always shown.
+    pub hide: Vec<AntragColumn>,
+    /// Width of a column in characters, e.g. `{ antragssteller = 24 }`.
+    /// Without one the Titel takes the space the others leave.
+    pub widths: HashMap<AntragColumn, u16>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AntragColumn {
+    Titel,
+    Antragssteller,
+    Status,
+    /// Only shown if any of the Anträge has a creation date.
+    Erstellt,
+}
+
+impl AntragColumn {
+    pub fn label(self) -> &'static str {
+        match self {
+            AntragColumn::Titel => "Titel",
+            AntragColumn::Antragssteller => "Antragssteller",
+            AntragColumn::Status => "Status",
+            AntragColumn::Erstellt => "Erstellt",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(default)]
+pub struct Theme {
+    pub header_bg: Color,
+    pub row_bg: Color,
+    pub selected_fg: Color,
+    pub text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            header_bg: tailwind::BLUE.c950,
+            row_bg: tailwind::SLATE.c950,
+            selected_fg: tailwind::BLUE.c300,
+            text: tailwind::SLATE.c200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeyMap {
+    bindings: HashMap<(KeyCode, KeyModifiers), (KeyCode, KeyModifiers)>,
+    /// The configured names of all keys bound to a key, for display.
+    aliases: HashMap<(KeyCode, KeyModifiers), Vec<String>>,
+}
+
+impl KeyMap {
+    pub fn translate(&self, key: KeyEvent) -> KeyEvent {
+        match self.bindings.get(&normalize(key.code, key.modifiers)) {
+            Some(&(code, modifiers)) => KeyEvent::new(code, modifiers),
+            None => key,
+        }
+    }
+
+    /// `key` together with every key bound to it, e.g. `l/o` for `"l" = "o"`.
+    /// Names that are not a single key are returned as they are.
+    pub fn label(&self, key: &str) -> String {
+        let aliases = parse_key(key).and_then(|key| self.aliases.get(&key));
+        match aliases {
+            Some(aliases) => format!("{}/{}", aliases.join("/"), key),
+            None => key.to_string(),
+        }
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("sitzungsverwaltung").join("config.toml"))
+}
+
+pub fn load() -> anyhow::Result<(Config, KeyMap)> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok((Config::default(), KeyMap::default()));
+    };
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let config: Config =
+        toml::from_str(&text).with_context(|| format!("invalid config {}", path.display()))?;
+    let keymap = keymap(&config)?;
+    Ok((config, keymap))
+}
+
+fn keymap(config: &Config) -> anyhow::Result<KeyMap> {
+    let mut bindings = HashMap::new();
+    let mut aliases: HashMap<_, Vec<String>> = HashMap::new();
+    for (name, to) in &config.keys {
+        let from = parse_key(name).ok_or_else(|| anyhow!("unknown key '{}'", name))?;
+        let to = parse_key(to).ok_or_else(|| anyhow!("unknown key '{}'", to))?;
+        bindings.insert(from, to);
+        aliases.entry(to).or_default().push(name.clone());
+    }
+    for names in aliases.values_mut() {
+        names.sort();
+    }
+    Ok(KeyMap { bindings, aliases })
+}
+
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    (
+        code,
+        modifiers.intersection(KeyModifiers::CONTROL | KeyModifiers::ALT),
+    )
+}
+
+pub fn parse_key(text: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = text;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("Ctrl+") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("Alt+") {
+            modifiers |= KeyModifiers::ALT;
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+    let code = match rest {
+        "Esc" => KeyCode::Esc,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Backspace,
+        "Space" => KeyCode::Char(' '),
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        _ => {
+            if let Some(n) = rest.strip_prefix('F').and_then(|n| n.parse().ok()) {
+                KeyCode::F(n)
+            } else {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        }
+    };
+    Some(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auth_modes_round_trip_through_their_config_text() {
+        for (text, auth) in [
+            ("cookie", AuthMode::Cookie),
+            ("bearer", AuthMode::Bearer),
+            (
+                "header:X-Api-Key",
+                AuthMode::Header("X-Api-Key".to_string()),
+            ),
+        ] {
+            assert_eq!(AuthMode::try_from(text.to_string()), Ok(auth.clone()));
+            assert_eq!(String::from(auth), text);
+        }
+        assert_eq!(
+            AuthMode::try_from("header: X-Token ".to_string()),
+            Ok(AuthMode::Header("X-Token".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_auth_modes_are_rejected() {
+        for text in ["", "Bearer", "basic", "header:", "header:  "] {
+            assert_eq!(
+                AuthMode::try_from(text.to_string()),
+                Err(format!(
+                    "unknown auth mode '{}', expected cookie, bearer or header:<name>",
+                    text
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::Local;
+
+/// What the app was doing, kept up to date by the render loop so the panic
+/// hook can write it out. Never holds the access token.
+static CONTEXT: Mutex<String> = Mutex::new(String::new());
+
+pub fn record(context: String) {
+    if let Ok(mut shared) = CONTEXT.lock() {
+        *shared = context;
+    }
+}
+
+/// Writes `panic` together with the last recorded context next to the
+/// config file and returns where.
+pub fn write_report(panic: &str) -> Option<PathBuf> {
+    let dir = crate::config::path()?.parent()?.to_path_buf();
+    std::fs::create_dir_all(&dir).ok()?;
+    let now = Local::now();
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    // The lock may be poisoned by the very panic being reported.
+    let context = match CONTEXT.lock() {
+        Ok(context) => context.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    let report = format!(
+        "sitzungsverwaltung {} crashed at {}\n\n{}\n\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.format("%Y-%m-%d %H:%M:%S"),
+        panic,
+        redact(&context, &[]),
+    );
+    std::fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+/// Masks `secrets` and anything that looks like a token in `text`.
+pub fn redact(text: &str, secrets: &[&str]) -> String {
+    let mut text = text.to_string();
+    for secret in secrets.iter().filter(|secret| !secret.is_empty()) {
+        text = text.replace(secret, "<redacted>");
+    }
+    for marker in ["access_token=", "Bearer ", "password=", "client_secret="] {
+        let mut redacted = String::new();
+        let mut rest = text.as_str();
+        while let Some(start) = rest.find(marker) {
+            let (before, after) = rest.split_at(start + marker.len());
+            redacted.push_str(before);
+            redacted.push_str("<redacted>");
+            let end = after
+                .find(|c: char| c.is_whitespace() || c == '&' || c == ';' || c == '"')
+                .unwrap_or(after.len());
+            rest = &after[end..];
+        }
+        redacted.push_str(rest);
+        text = redacted;
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_tokens_and_given_secrets() {
+        let text = "GET /api?access_token=abc.def&x=1\n\
+                    Authorization: Bearer eyJhbGci\n\
+                    token eyJsecret in the log";
+        assert_eq!(
+            redact(text, &["eyJsecret"]),
+            "GET /api?access_token=<redacted>&x=1\n\
+             Authorization: Bearer <redacted>\n\
+             token <redacted> in the log"
+        );
+    }
+}
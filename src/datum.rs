@@ -0,0 +1,149 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+const STRICT_FORMATS: [&str; 5] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+];
+
+/// Parses either a relative expression like "next thursday 18:00" or
+/// "morgen 20:00", or one of the absolute formats in `STRICT_FORMATS`.
+pub fn parse(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    parse_relative(input, now).or_else(|| parse_strict(input))
+}
+
+fn parse_strict(input: &str) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    STRICT_FORMATS.iter().find_map(|format| {
+        NaiveDateTime::parse_from_str(input, format)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(input, format)
+                    .ok()
+                    .map(|date| date.and_time(NaiveTime::MIN))
+            })
+    })
+}
+
+fn parse_relative(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let lowered = input.trim().to_lowercase();
+    let mut words = lowered.split_whitespace().peekable();
+    let today = now.date();
+
+    let next = matches!(
+        words.peek(),
+        Some(&("next" | "nächsten" | "nächster" | "kommenden"))
+    );
+    if next {
+        words.next();
+    }
+
+    let date = match words.next()? {
+        "today" | "heute" if !next => today,
+        "tomorrow" | "morgen" if !next => today + Duration::days(1),
+        word => {
+            let weekday = parse_weekday(word)?;
+            let mut days =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            if next && days == 0 {
+                days = 7;
+            }
+            today + Duration::days(days as i64)
+        }
+    };
+
+    let time = match words.next() {
+        Some(time) => parse_time(time)?,
+        None => NaiveTime::MIN,
+    };
+    if words.next().is_some() {
+        return None;
+    }
+    Some(date.and_time(time))
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    Some(match word {
+        "monday" | "montag" => Weekday::Mon,
+        "tuesday" | "dienstag" => Weekday::Tue,
+        "wednesday" | "mittwoch" => Weekday::Wed,
+        "thursday" | "donnerstag" => Weekday::Thu,
+        "friday" | "freitag" => Weekday::Fri,
+        "saturday" | "samstag" => Weekday::Sat,
+        "sunday" | "sonntag" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    let word = word.trim_end_matches("uhr");
+    NaiveTime::parse_from_str(word, "%H:%M").ok().or_else(|| {
+        word.parse()
+            .ok()
+            .and_then(|h| NaiveTime::from_hms_opt(h, 0, 0))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A Wednesday.
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 5, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_relative_days() {
+        assert_eq!(parse("tomorrow 20:00", now()), Some(at(2024, 5, 2, 20, 0)));
+        assert_eq!(parse("heute 18", now()), Some(at(2024, 5, 1, 18, 0)));
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(
+            parse("next thursday 18:00", now()),
+            Some(at(2024, 5, 2, 18, 0))
+        );
+        assert_eq!(parse("wednesday 18:00", now()), Some(at(2024, 5, 1, 18, 0)));
+        assert_eq!(
+            parse("next wednesday 18:00", now()),
+            Some(at(2024, 5, 8, 18, 0))
+        );
+        assert_eq!(
+            parse("Donnerstag 18:30", now()),
+            Some(at(2024, 5, 2, 18, 30))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_strict_formats() {
+        assert_eq!(
+            parse("2024-05-03 18:00:00", now()),
+            Some(at(2024, 5, 3, 18, 0))
+        );
+        assert_eq!(
+            parse("03.05.2024 18:00", now()),
+            Some(at(2024, 5, 3, 18, 0))
+        );
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert_eq!(parse("sometime soon", now()), None);
+        assert_eq!(parse("next tomorrow", now()), None);
+        assert_eq!(parse("tomorrow 25:00", now()), None);
+    }
+}
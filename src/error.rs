@@ -0,0 +1,185 @@
+use std::fmt;
+
+use ratatui::style::{palette::tailwind, Color};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+/// Why a request to the backend failed, sorted by what the user can do
+/// about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// The backend could not be reached.
+    Network(String),
+    Timeout,
+    /// 401 or 403, the login expired or lacks the rights.
+    Auth(u16),
+    /// Any 5xx.
+    Server(u16),
+    NotFound,
+    /// Someone else saved the item in between, 409.
+    Conflict,
+    /// The backend answered with something that is not what we expect.
+    Deserialize(String),
+    /// 400 or 422, with what the backend said about the input.
+    Validation(String),
+    /// Any other error status.
+    Status(u16),
+}
+
+impl AppError {
+    /// Maps an error status, `body` is only kept for rejected input.
+    pub fn from_status(status: StatusCode, body: &str) -> Self {
+        match status.as_u16() {
+            401 | 403 => AppError::Auth(status.as_u16()),
+            404 => AppError::NotFound,
+            409 => AppError::Conflict,
+            400 | 422 => AppError::Validation(validation_message(body)),
+            code if status.is_server_error() => AppError::Server(code),
+            code => AppError::Status(code),
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            AppError::Network(_) => "⚡",
+            AppError::Timeout => "⌛",
+            AppError::Auth(_) => "⚿",
+            AppError::Server(_) => "✖",
+            AppError::NotFound => "∅",
+            AppError::Conflict => "⇄",
+            AppError::Deserialize(_) => "≠",
+            AppError::Validation(_) => "✎",
+            AppError::Status(_) => "!",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            AppError::Network(_) | AppError::Timeout => tailwind::AMBER.c400,
+            AppError::Auth(_) => tailwind::VIOLET.c400,
+            AppError::Server(_) | AppError::Status(_) => tailwind::RED.c400,
+            AppError::NotFound => tailwind::SLATE.c400,
+            AppError::Conflict => tailwind::ORANGE.c400,
+            AppError::Deserialize(_) => tailwind::FUCHSIA.c400,
+            AppError::Validation(_) => tailwind::ROSE.c400,
+        }
+    }
+
+    /// What the user can do about it.
+    pub fn action(&self) -> &'static str {
+        match self {
+            AppError::Network(_) => "check the connection",
+            AppError::Timeout => "try again",
+            AppError::Auth(_) => "Ctrl+T to log in again",
+            AppError::Server(_) | AppError::Status(_) => "check the server",
+            AppError::NotFound => "it may have been deleted, reload",
+            AppError::Conflict => "reload and apply your changes again",
+            AppError::Deserialize(_) => "check the backend version",
+            AppError::Validation(_) => "fix the input",
+        }
+    }
+
+    /// Whether sending the same request again may succeed.
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            AppError::Network(_) | AppError::Timeout | AppError::Server(_)
+        )
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Network(err) => write!(f, "backend not reachable ({})", err),
+            AppError::Timeout => write!(f, "the backend did not answer in time"),
+            AppError::Auth(code) => write!(f, "not authorized ({})", code),
+            AppError::Server(code) => write!(f, "server error ({})", code),
+            AppError::NotFound => write!(f, "not found"),
+            AppError::Conflict => write!(f, "changed by someone else"),
+            AppError::Deserialize(err) => write!(f, "unexpected answer ({})", err),
+            AppError::Validation(message) if message.is_empty() => write!(f, "input rejected"),
+            AppError::Validation(message) => write!(f, "input rejected: {}", message),
+            AppError::Status(code) => write!(f, "request failed ({})", code),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<reqwest::Error> for AppError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            AppError::Timeout
+        } else if let Some(status) = err.status() {
+            AppError::from_status(status, "")
+        } else if err.is_decode() {
+            AppError::Deserialize(err.to_string())
+        } else {
+            AppError::Network(err.to_string())
+        }
+    }
+}
+
+/// Passes a successful response through and turns an error status into an
+/// `AppError`, reading the body of rejected input for its message.
+pub async fn check(response: reqwest::Response) -> Result<reqwest::Response, AppError> {
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return Ok(response);
+    }
+    let body = match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+            response.text().await.unwrap_or_default()
+        }
+        _ => String::new(),
+    };
+    Err(AppError::from_status(status, &body))
+}
+
+/// The `detail` or `message` of a JSON error body, or the body itself.
+fn validation_message(body: &str) -> String {
+    let from_json = serde_json::from_str::<Value>(body).ok().and_then(|json| {
+        ["detail", "message"]
+            .iter()
+            .find_map(|key| json.get(key))
+            .map(|value| match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+    });
+    let message = from_json.unwrap_or_else(|| body.to_string());
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_map_to_what_the_user_can_do() {
+        let error = |code| AppError::from_status(StatusCode::from_u16(code).unwrap(), "");
+        assert_eq!(error(401), AppError::Auth(401));
+        assert_eq!(error(403), AppError::Auth(403));
+        assert_eq!(error(404), AppError::NotFound);
+        assert_eq!(error(409), AppError::Conflict);
+        assert_eq!(error(503), AppError::Server(503));
+        assert_eq!(error(418), AppError::Status(418));
+        assert!(error(502).retryable());
+        assert!(!error(401).retryable());
+    }
+
+    #[test]
+    fn rejected_input_keeps_what_the_backend_said() {
+        let rejected = |body| AppError::from_status(StatusCode::UNPROCESSABLE_ENTITY, body);
+        assert_eq!(
+            rejected(r#"{"detail": "titel darf nicht leer sein"}"#),
+            AppError::Validation("titel darf nicht leer sein".to_string())
+        );
+        assert_eq!(
+            rejected("bad\n  request"),
+            AppError::Validation("bad request".to_string())
+        );
+        assert_eq!(rejected("").to_string(), "input rejected");
+    }
+}
@@ -0,0 +1,349 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::backend::{self, Reader, Response};
+use crate::{base_url, config, inhalt_to_text, keycloak, set_base_url, Antrag, Sitzung, Top, URL};
+
+/// What the export needs to log in without a prompt, unless `TOKEN` is set.
+const LOGIN_VARS: [&str; 4] = ["USERNAME", "PASSWORD", "CLIENT_ID", "CLIENT_SECRET"];
+
+/// `sitzungsverwaltung export --sitzung <id> [--format md] [--out <file>]`
+///
+/// Reads with the token in `TOKEN`, or logs in with [`LOGIN_VARS`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Options {
+    pub sitzung: Uuid,
+    /// Written to stdout without one.
+    pub out: Option<PathBuf>,
+    pub insecure: bool,
+}
+
+impl Options {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut sitzung = None;
+        let mut out = None;
+        let mut insecure = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value", arg));
+            match arg.as_str() {
+                "--sitzung" => {
+                    let id = value()?;
+                    sitzung = Some(id.parse().with_context(|| format!("invalid id '{}'", id))?);
+                }
+                "--format" => match value()?.as_str() {
+                    "md" => {}
+                    other => bail!("unknown format '{}', only md is supported", other),
+                },
+                "--out" => out = Some(PathBuf::from(value()?)),
+                "--insecure" => insecure = true,
+                other => bail!("unknown argument '{}'", other),
+            }
+        }
+        Ok(Options {
+            sitzung: sitzung.context("--sitzung <id> is required")?,
+            out,
+            insecure,
+        })
+    }
+}
+
+/// How the export authenticates.
+#[derive(Debug, PartialEq, Eq)]
+enum Credentials {
+    Token(String),
+    /// All of [`LOGIN_VARS`] are set.
+    Login,
+}
+
+impl Credentials {
+    /// Looks the variables up with `var`, `std::env::var` outside of tests.
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let set = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        if let Some(token) = set("TOKEN") {
+            return Ok(Credentials::Token(token.trim().to_string()));
+        }
+        let missing: Vec<_> = LOGIN_VARS
+            .into_iter()
+            .filter(|name| set(name).is_none())
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "set TOKEN, or {} to log in (missing {})",
+                LOGIN_VARS.join(", "),
+                missing.join(", ")
+            );
+        }
+        Ok(Credentials::Login)
+    }
+
+    async fn token(self) -> anyhow::Result<String> {
+        match self {
+            Credentials::Token(token) => Ok(token),
+            Credentials::Login => {
+                let token = keycloak::login(keycloak::LOGIN_TIMEOUT, std::future::pending())
+                    .await
+                    .context("could not log in")?;
+                Ok(token.secret)
+            }
+        }
+    }
+}
+
+/// Fetches a Sitzung with its Tops and Anträge and writes it as Markdown,
+/// without starting the TUI.
+pub async fn run(args: &[String]) -> anyhow::Result<()> {
+    let options = Options::parse(args)?;
+    let (config, _) = config::load()?;
+    set_base_url(&config);
+    let insecure = (options.insecure || config.insecure) && base_url() != URL;
+    let token = Credentials::from_env(|name| std::env::var(name).ok())?
+        .token()
+        .await?;
+    let client = backend::client(insecure);
+    let base = base_url();
+    let reader = Reader {
+        client: &client,
+        base: &base,
+        auth: Some((&config.auth, &token)),
+    };
+    let (events, mut malformed) = mpsc::unbounded_channel();
+
+    let url = format!("{}api/topmanager/sitzung/{}/", base, options.sitzung);
+    let sitzung: Sitzung = backend::fetch(reader, url)
+        .await
+        .context("could not load the Sitzung")?;
+    let agenda = backend::fetch_agenda(reader, sitzung.id, &events)
+        .await
+        .context("could not load the Tops")?;
+    drop(events);
+    while let Some(event) = malformed.recv().await {
+        if let Response::Malformed { url, count } = event {
+            eprintln!("Skipped {} unreadable entries from {}", count, url);
+        }
+    }
+    for failure in &agenda.failed {
+        eprintln!("Skipped Top {}", failure);
+    }
+    if let Some(partial) = agenda.partial("Exported") {
+        eprintln!("{}", partial);
+    }
+
+    let text = markdown(&sitzung, &agenda.tops);
+    match &options.out {
+        Some(path) => std::fs::write(path, text)
+            .with_context(|| format!("could not write {}", path.display()))?,
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// The protocol of a Sitzung, one section per Top in agenda order.
+pub fn markdown(sitzung: &Sitzung, agenda: &[(Top, Vec<Antrag>)]) -> String {
+    let mut text = format!(
+        "# {}\n\n{}\n",
+        sitzung.name,
+        sitzung.datum.format("%Y-%m-%d %H:%M")
+    );
+    if !sitzung.anwesend.is_empty() {
+        text.push_str(&format!("\nAnwesend: {}\n", sitzung.anwesend.join(", ")));
+    }
+    for (number, (top, anträge)) in agenda.iter().enumerate() {
+        text.push_str(&format!("\n## Top {}: {}\n", number + 1, top.name));
+        if let Some(verantwortlich) = top.verantwortlich.as_deref().filter(|v| !v.is_empty()) {
+            text.push_str(&format!("\nVorgestellt von: {}\n", verantwortlich));
+        }
+        let inhalt = inhalt_to_text(&top.inhalt);
+        if !inhalt.trim().is_empty() {
+            text.push_str(&format!("\n{}\n", inhalt.trim()));
+        }
+        for antrag in anträge {
+            text.push_str(&format!(
+                "\n### {} ({})\n",
+                antrag.titel,
+                antrag.status.label()
+            ));
+            if !antrag.begründung.trim().is_empty() {
+                text.push_str(&format!(
+                    "\n**Begründung**\n\n{}\n",
+                    antrag.begründung.trim()
+                ));
+            }
+            text.push_str(&format!(
+                "\n**Antragstext**\n\n{}\n",
+                antrag.antragstext.trim()
+            ));
+        }
+        if !top.protokoll.trim().is_empty() {
+            text.push_str(&format!("\n**Protokoll**\n\n{}\n", top.protokoll.trim()));
+        }
+    }
+    text
+}
+
+/// A single Top on its own, with its Inhalt as text or as a JSON block.
+pub fn top(top: &Top) -> String {
+    let mut text = format!("# {}\n", top.name);
+    if let Some(verantwortlich) = top.verantwortlich.as_deref().filter(|v| !v.is_empty()) {
+        text.push_str(&format!("\nVorgestellt von: {}\n", verantwortlich));
+    }
+    match &top.inhalt {
+        Value::Array(_) | Value::Object(_) => text.push_str(&format!(
+            "\n## Inhalt\n\n```json\n{}\n```\n",
+            inhalt_to_text(&top.inhalt)
+        )),
+        inhalt => {
+            let inhalt = inhalt_to_text(inhalt);
+            if !inhalt.trim().is_empty() {
+                text.push_str(&format!("\n## Inhalt\n\n{}\n", inhalt.trim()));
+            }
+        }
+    }
+    text
+}
+
+/// A single Antrag on its own, with a section each for Begründung and
+/// Antragstext.
+pub fn antrag(antrag: &Antrag) -> String {
+    let mut text = format!("# {}\n\nStatus: {}\n", antrag.titel, antrag.status.label());
+    if let Some(author) = antrag.author.as_deref().filter(|a| !a.is_empty()) {
+        text.push_str(&format!("Antragssteller: {}\n", author));
+    }
+    for (heading, section) in [
+        ("Begründung", &antrag.begründung),
+        ("Antragstext", &antrag.antragstext),
+    ] {
+        if !section.trim().is_empty() {
+            text.push_str(&format!("\n## {}\n\n{}\n", heading, section.trim()));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_export_arguments() {
+        let id = Uuid::new_v4();
+        let options =
+            Options::parse(&args(&format!("--sitzung {} --format md --out p.md", id))).unwrap();
+        assert_eq!(
+            options,
+            Options {
+                sitzung: id,
+                out: Some(PathBuf::from("p.md")),
+                insecure: false,
+            }
+        );
+        assert!(Options::parse(&args("--format md")).is_err());
+        assert!(Options::parse(&args(&format!("--sitzung {} --format pdf", id))).is_err());
+        assert!(Options::parse(&args("--sitzung")).is_err());
+    }
+
+    #[test]
+    fn needs_a_token_or_everything_to_log_in() {
+        let env = |vars: &'static [(&str, &str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            Credentials::from_env(env(&[("TOKEN", "abc"), ("USERNAME", "kim")])).unwrap(),
+            Credentials::Token("abc".to_string())
+        );
+        assert_eq!(
+            Credentials::from_env(env(&[
+                ("USERNAME", "kim"),
+                ("PASSWORD", "geheim"),
+                ("CLIENT_ID", "tui"),
+                ("CLIENT_SECRET", "s"),
+            ]))
+            .unwrap(),
+            Credentials::Login
+        );
+        let err = Credentials::from_env(env(&[("TOKEN", " "), ("USERNAME", "kim")])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "set TOKEN, or USERNAME, PASSWORD, CLIENT_ID, CLIENT_SECRET to log in \
+             (missing PASSWORD, CLIENT_ID, CLIENT_SECRET)"
+        );
+    }
+
+    #[test]
+    fn writes_tops_in_agenda_order_with_their_anträge() {
+        let sitzung = Sitzung {
+            name: "Sitzung 3".to_string(),
+            datum: "2024-05-02T18:00:00".parse().unwrap(),
+            anwesend: vec!["Alex".to_string(), "Kim".to_string()],
+            ..Sitzung::default()
+        };
+        let top = |name: &str| Top {
+            name: name.to_string(),
+            ..Top::default()
+        };
+        let antrag = Antrag {
+            titel: "Kaffee".to_string(),
+            antragstext: "Der FSR möge...".to_string(),
+            ..Antrag::default()
+        };
+        let text = markdown(
+            &sitzung,
+            &[(top("Begrüßung"), vec![]), (top("Finanzen"), vec![antrag])],
+        );
+        assert_eq!(
+            text,
+            "# Sitzung 3\n\n2024-05-02 18:00\n\
+             \nAnwesend: Alex, Kim\n\
+             \n## Top 1: Begrüßung\n\
+             \n## Top 2: Finanzen\n\
+             \n### Kaffee (eingereicht)\n\
+             \n**Antragstext**\n\nDer FSR möge...\n"
+        );
+    }
+
+    #[test]
+    fn copies_a_single_top_or_antrag_with_headings() {
+        let top = Top {
+            name: "Finanzen".to_string(),
+            verantwortlich: Some("Kim".to_string()),
+            inhalt: serde_json::json!({ "budget": 200 }),
+            ..Top::default()
+        };
+        assert_eq!(
+            super::top(&top),
+            "# Finanzen\n\nVorgestellt von: Kim\n\n## Inhalt\n\n\
+             ```json\n{\n  \"budget\": 200\n}\n```\n"
+        );
+        let top = Top {
+            name: "Begrüßung".to_string(),
+            inhalt: serde_json::json!(" Hallo "),
+            ..Top::default()
+        };
+        assert_eq!(super::top(&top), "# Begrüßung\n\n## Inhalt\n\nHallo\n");
+        let antrag = Antrag {
+            titel: "Kaffee".to_string(),
+            begründung: "Müde".to_string(),
+            antragstext: "Der FSR möge...".to_string(),
+            author: Some("Alex".to_string()),
+            ..Antrag::default()
+        };
+        assert_eq!(
+            super::antrag(&antrag),
+            "# Kaffee\n\nStatus: eingereicht\nAntragssteller: Alex\n\
+             \n## Begründung\n\nMüde\n\
+             \n## Antragstext\n\nDer FSR möge...\n"
+        );
+    }
+}
@@ -0,0 +1,509 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Language of the labels around the data, the data itself is shown as
+/// the backend sends it.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+}
+
+impl Lang {
+    pub fn toggle(self) -> Self {
+        match self {
+            Lang::En => Lang::De,
+            Lang::De => Lang::En,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lang::En => "English",
+            Lang::De => "Deutsch",
+        }
+    }
+}
+
+/// `text` in `lang`. The English text is the key, texts without a
+/// translation are returned as they are.
+pub fn tr(lang: Lang, text: &str) -> &str {
+    let table = match lang {
+        Lang::En => return text,
+        Lang::De => GERMAN,
+    };
+    table
+        .iter()
+        .find(|(key, _)| *key == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// `text` in `lang` like `tr`, also when it was built from a template with
+/// `{}` placeholders, as status messages are. The parts filled in are
+/// translated on their own, so e.g. the action suggested for an error is
+/// translated along with the message around it. If several templates
+/// fit, the one with the most fixed text wins.
+pub fn tr_message(lang: Lang, text: &str) -> Cow<'_, str> {
+    let table = match lang {
+        Lang::En => return Cow::Borrowed(text),
+        Lang::De => GERMAN,
+    };
+    if let Some((_, translated)) = table.iter().find(|(key, _)| *key == text) {
+        return Cow::Borrowed(translated);
+    }
+    let best = table
+        .iter()
+        .filter(|(key, _)| key.contains("{}"))
+        .filter_map(|(key, translated)| Some((key.len(), translated, filled_in(key, text)?)))
+        .max_by_key(|(len, ..)| *len);
+    let Some((_, translated, parts)) = best else {
+        return Cow::Borrowed(text);
+    };
+    let parts: Vec<_> = parts
+        .into_iter()
+        .map(|part| tr_message(lang, part))
+        .collect();
+    let parts: Vec<&dyn Display> = parts.iter().map(|part| part as &dyn Display).collect();
+    Cow::Owned(fill(translated, &parts))
+}
+
+/// `template` with its `{}` placeholders replaced by `parts` in order.
+pub fn fill(template: &str, parts: &[&dyn Display]) -> String {
+    let mut literals = template.split("{}");
+    let mut filled = literals.next().unwrap_or_default().to_string();
+    for (literal, part) in literals.zip(parts) {
+        filled.push_str(&part.to_string());
+        filled.push_str(literal);
+    }
+    filled
+}
+
+/// The parts of `text` that fill the `{}` placeholders of `template`, if
+/// it was built from it. Each part ends at the first place the literal
+/// text after it follows.
+fn filled_in<'t>(template: &str, text: &'t str) -> Option<Vec<&'t str>> {
+    let mut literals = template.split("{}");
+    let mut rest = text.strip_prefix(literals.next()?)?;
+    let literals: Vec<&str> = literals.collect();
+    let mut parts = vec![];
+    for (index, literal) in literals.iter().enumerate() {
+        if index + 1 == literals.len() {
+            parts.push(rest.strip_suffix(literal)?);
+        } else {
+            let (part, after) = rest.split_once(literal)?;
+            parts.push(part);
+            rest = after;
+        }
+    }
+    Some(parts)
+}
+
+const GERMAN: &[(&str, &str)] = &[
+    // Titles
+    ("read-only", "nur lesen"),
+    ("Edit Sitzung", "Sitzung bearbeiten"),
+    ("Edit Top", "Top bearbeiten"),
+    ("Edit Antrag", "Antrag bearbeiten"),
+    ("Create Sitzung", "Neue Sitzung"),
+    ("Create Top", "Neuer Top"),
+    ("Create Antrag", "Neuer Antrag"),
+    ("Edit Value", "Wert bearbeiten"),
+    // Popup titles
+    ("Agenda", "Tagesordnung"),
+    ("Anträge by \"{}\"", "Anträge von \"{}\""),
+    ("Attendance", "Anwesenheit"),
+    ("Changes", "Änderungen"),
+    ("Conflict", "Konflikt"),
+    ("Dashboard", "Übersicht"),
+    ("Deleted", "Gelöschte"),
+    ("Details", "Details"),
+    ("Import", "Import"),
+    ("Import Anträge", "Anträge importieren"),
+    ("Log", "Protokoll der Aktionen"),
+    ("Merge", "Zusammenführen"),
+    (
+        "Move the Tops of '{}' to",
+        "Die Tops von '{}' verschieben nach",
+    ),
+    ("Next Sitzung", "Nächste Sitzung"),
+    ("Open Anträge", "Offene Anträge"),
+    ("Path to a Markdown file", "Pfad zu einer Markdown-Datei"),
+    ("Payload Preview", "Vorschau der Anfrage"),
+    ("Profiles", "Profile"),
+    ("Protocol", "Protokoll"),
+    ("Quit", "Beenden"),
+    ("Recent", "Zuletzt geöffnet"),
+    ("Stale copy", "Veraltete Kopie"),
+    ("Unsaved changes", "Ungespeicherte Änderungen"),
+    ("Upcoming Sitzungen", "Kommende Sitzungen"),
+    ("entries", "Einträge"),
+    ("loading…", "lädt…"),
+    ("present", "anwesend"),
+    // Lists, prompts and popups
+    (
+        "Sitzungen — {} pinned, {} upcoming, {} past",
+        "Sitzungen — {} angeheftet, {} kommend, {} vergangen",
+    ),
+    (
+        "Sitzungen — {} pinned, {} upcoming, {} past, hidden",
+        "Sitzungen — {} angeheftet, {} kommend, {} vergangen, ausgeblendet",
+    ),
+    ("Tops — {} — with open Anträge", "Tops — {} — mit offenen Anträgen"),
+    (" — filter \"{}\"", " — Filter \"{}\""),
+    (" — {} loading {}s, Esc to cancel", " — {} lädt {}s, Esc zum Abbrechen"),
+    ("Top {} of {} — {}%", "Top {} von {} — {}%"),
+    ("No matches", "Keine Treffer"),
+    ("No Anträge for this Top", "Keine Anträge zu diesem Top"),
+    ("No Sitzungen", "Keine Sitzungen"),
+    ("No upcoming Sitzungen", "Keine kommenden Sitzungen"),
+    ("Terminal too small\nneeds at least {}x{}", "Terminal zu klein\nbenötigt mindestens {}x{}"),
+    ("Type-ahead", "Direktsuche"),
+    ("New Top", "Neuer Top"),
+    ("New Antrag", "Neuer Antrag"),
+    ("Author", "Antragssteller"),
+    (
+        "Save before leaving?\n\ny/Enter to save, n to discard, Esc to keep editing",
+        "Vor dem Verlassen speichern?\n\ny/Enter zum Speichern, n zum Verwerfen, Esc zum Weiterbearbeiten",
+    ),
+    ("Looking through all Tops for \"{}\"…", "Alle Tops werden nach \"{}\" durchsucht…"),
+    (
+        "You have unsaved edits — save / discard / cancel?\n\ns/Enter to save them and quit, d to quit without them, Esc to go back",
+        "Es gibt ungespeicherte Änderungen — speichern / verwerfen / abbrechen?\n\ns/Enter zum Speichern und Beenden, d zum Beenden ohne sie, Esc zurück",
+    ),
+    (
+        "This item changed on the server — reload and reapply?\n\ny/Enter to fetch it again and see your changes on top of it, n/Esc to dismiss",
+        "Dieser Eintrag wurde auf dem Server geändert — neu laden und erneut anwenden?\n\ny/Enter zum neu Laden mit deinen Änderungen darauf, n/Esc zum Ausblenden",
+    ),
+    (
+        "This item was fetched {} minutes ago and may have changed since.\n\ny/Enter to fetch it again and see your changes on top of it, n to save over it anyway, Esc to go back",
+        "Dieser Eintrag wurde vor {} Minuten geladen und kann sich seitdem geändert haben.\n\ny/Enter zum neu Laden mit deinen Änderungen darauf, n zum trotzdem Speichern, Esc zurück",
+    ),
+    ("Creating Antrag {}/{}… {}", "Antrag {}/{} wird angelegt… {}"),
+    ("Moving Top {}/{}… {}", "Top {}/{} wird verschoben… {}"),
+    ("Esc to cancel", "Esc zum Abbrechen"),
+    ("{} of {} Tops moved", "{} von {} Tops verschoben"),
+    (
+        "'{}' is empty now. Delete it? y to delete, any other key to keep",
+        "'{}' ist jetzt leer. Löschen? y zum Löschen, jede andere Taste zum Behalten",
+    ),
+    ("{} created, {} failed", "{} angelegt, {} fehlgeschlagen"),
+    (", {} skipped after cancelling", ", {} nach dem Abbrechen übersprungen"),
+    // Footer hints
+    ("any key", "beliebige Taste"),
+    ("Anträge by author", "Anträge nach Antragssteller"),
+    ("action log", "Protokoll der Aktionen"),
+    ("add Antrag", "Antrag hinzufügen"),
+    ("remove Antrag", "Antrag entfernen"),
+    ("agenda one-liner", "Tagesordnung in einer Zeile"),
+    ("attachment", "Anhang"),
+    ("attendance", "Anwesenheit"),
+    ("back", "zurück"),
+    ("back to the list", "zurück zur Liste"),
+    ("back/forward", "zurück/vor"),
+    ("cancel", "abbrechen"),
+    ("clear", "leeren"),
+    ("clear filter", "Filter leeren"),
+    ("close", "schließen"),
+    ("compact", "kompakt"),
+    ("copy", "kopieren"),
+    ("copy Markdown", "als Markdown kopieren"),
+    ("copy as JSON", "als JSON kopieren"),
+    ("copy as Markdown", "als Markdown kopieren"),
+    ("copy id", "Id kopieren"),
+    ("copy link", "Link kopieren"),
+    ("create", "anlegen"),
+    ("dashboard", "Übersicht"),
+    ("delete", "löschen"),
+    ("delete empty Sitzung", "leere Sitzung löschen"),
+    ("discard", "verwerfen"),
+    ("discard and quit", "verwerfen und beenden"),
+    ("dismiss", "ausblenden"),
+    ("done", "fertig"),
+    ("duplicate", "duplizieren"),
+    ("edit", "bearbeiten"),
+    ("edit value", "Wert bearbeiten"),
+    ("export PDF", "als PDF exportieren"),
+    ("fetch again", "neu laden"),
+    ("filter", "filtern"),
+    ("focus details", "Details fokussieren"),
+    ("focus mode", "Fokusmodus"),
+    ("fold all", "alle einklappen"),
+    ("fold section", "Abschnitt einklappen"),
+    ("full screen", "Vollbild"),
+    ("import", "importieren"),
+    ("keep editing", "weiter bearbeiten"),
+    ("language", "Sprache"),
+    ("line start/end", "Zeilenanfang/-ende"),
+    ("log in again", "neu anmelden"),
+    ("merge", "zusammenführen"),
+    ("merge into", "zusammenführen mit"),
+    ("move", "bewegen"),
+    ("new", "neu"),
+    ("next/previous missing", "nächstes/voriges fehlendes Feld"),
+    ("notes", "Notizen"),
+    ("only open", "nur offene"),
+    ("open", "öffnen"),
+    ("pin", "anheften"),
+    ("present/absent", "anwesend/abwesend"),
+    ("preview payload", "Vorschau der Anfrage"),
+    ("previous/next Sitzung", "vorige/nächste Sitzung"),
+    ("previous/next Top", "voriger/nächster Top"),
+    ("profiles", "Profile"),
+    ("quick new", "schnell neu"),
+    ("quit", "beenden"),
+    ("raw JSON", "JSON-Rohdaten"),
+    ("read file", "Datei lesen"),
+    ("read protocol", "Protokoll lesen"),
+    ("recent", "zuletzt"),
+    ("refresh", "aktualisieren"),
+    ("reload and reapply", "neu laden und erneut anwenden"),
+    ("reload config", "Konfiguration neu laden"),
+    ("rename", "umbenennen"),
+    ("renumber weights", "neu nummerieren"),
+    ("reset to template", "auf Vorlage zurücksetzen"),
+    ("restore", "wiederherstellen"),
+    (
+        "retry failed request",
+        "fehlgeschlagene Anfrage wiederholen",
+    ),
+    ("save", "speichern"),
+    ("save and close", "speichern und schließen"),
+    ("save and quit", "speichern und beenden"),
+    ("save anyway", "trotzdem speichern"),
+    ("scroll", "blättern"),
+    ("search", "suchen"),
+    ("show changes", "Änderungen zeigen"),
+    ("show parents", "übergeordnete zeigen"),
+    ("show/hide past", "vergangene ein/aus"),
+    ("sort key/order", "Sortierung/Reihenfolge"),
+    ("switch", "wechseln"),
+    ("table/list", "Tabelle/Liste"),
+    ("today", "heute"),
+    ("trash", "Papierkorb"),
+    ("type-ahead", "Direktsuche"),
+    ("wrap", "umbrechen"),
+    // Status messages
+    ("All Tops", "Alle Tops"),
+    ("All fields are filled in", "Alle Felder sind ausgefüllt"),
+    ("Cancelling import…", "Import wird abgebrochen…"),
+    (
+        "Clear the filter first, all Tops have to be listed",
+        "Erst den Filter leeren, alle Tops müssen angezeigt werden",
+    ),
+    ("Config reloaded", "Konfiguration neu geladen"),
+    ("Copied as JSON", "Als JSON kopiert"),
+    ("Copied as Markdown", "Als Markdown kopiert"),
+    ("Copied the JSON", "JSON kopiert"),
+    ("Copied the agenda", "Tagesordnung kopiert"),
+    (
+        "Copied the protocol as Markdown",
+        "Protokoll als Markdown kopiert",
+    ),
+    ("Loading cancelled", "Laden abgebrochen"),
+    ("Logging in…", "Anmelden…"),
+    ("Looking up the Sitzung…", "Sitzung wird gesucht…"),
+    ("Looking up the Top…", "Top wird gesucht…"),
+    ("No Antrag selected", "Kein Antrag ausgewählt"),
+    (
+        "No Sitzung today or later",
+        "Keine Sitzung heute oder später",
+    ),
+    (
+        "No Sitzung today, selected the next one",
+        "Heute keine Sitzung, die nächste ist ausgewählt",
+    ),
+    ("No Tops to list", "Keine Tops vorhanden"),
+    (
+        "No members configured, add a members list to the config",
+        "Keine Mitglieder konfiguriert, members in der Konfiguration ergänzen",
+    ),
+    ("No profiles configured", "Keine Profile konfiguriert"),
+    (
+        "No template for this field",
+        "Keine Vorlage für dieses Feld",
+    ),
+    ("Nothing opened yet", "Noch nichts geöffnet"),
+    ("Nothing selected", "Nichts ausgewählt"),
+    (
+        "Only Tops with open Anträge",
+        "Nur Tops mit offenen Anträgen",
+    ),
+    ("Re-authenticated", "Neu angemeldet"),
+    (
+        "Read-only, log in to renumber",
+        "Nur lesen, zum Nummerieren anmelden",
+    ),
+    (
+        "Read-only, log in to save the attendance",
+        "Nur lesen, zum Speichern der Anwesenheit anmelden",
+    ),
+    ("Reset to the template", "Auf Vorlage zurückgesetzt"),
+    ("Retrying…", "Wird wiederholt…"),
+    ("Saved", "Gespeichert"),
+    ("Saving before quitting…", "Speichern vor dem Beenden…"),
+    (
+        "Saving failed, not quitting",
+        "Speichern fehlgeschlagen, nicht beendet",
+    ),
+    ("Saving…", "Speichern…"),
+    (
+        "There is no other Sitzung to merge into",
+        "Es gibt keine andere Sitzung zum Zusammenführen",
+    ),
+    ("Top created", "Top angelegt"),
+    (
+        "Weights are already consecutive",
+        "Die Reihenfolge ist schon lückenlos",
+    ),
+    (
+        "Your changes on top of the server version, Ctrl+S to save",
+        "Deine Änderungen auf dem Stand des Servers, Ctrl+S zum Speichern",
+    ),
+    ("{} entries from {} could not be read", "{} Einträge von {} konnten nicht gelesen werden"),
+    ("Renumbering Tops… {}/{}", "Tops werden neu nummeriert… {}/{}"),
+    ("Re-authentication failed: {}", "Neuanmeldung fehlgeschlagen: {}"),
+    ("Could not save pins: {}", "Angeheftete Sitzungen konnten nicht gespeichert werden: {}"),
+    ("Sorted by {}", "Sortiert nach {}"),
+    ("Config error: {}", "Fehler in der Konfiguration: {}"),
+    ("Copy failed: {}", "Kopieren fehlgeschlagen: {}"),
+    ("Copied {}", "{} kopiert"),
+    ("{} (copy failed: {})", "{} (Kopieren fehlgeschlagen: {})"),
+    ("Copied id {}", "Id {} kopiert"),
+    ("Id {} (copy failed: {})", "Id {} (Kopieren fehlgeschlagen: {})"),
+    ("Renaming to \"{}\"…", "Wird umbenannt in \"{}\"…"),
+    ("Creating \"{}\"…", "\"{}\" wird angelegt…"),
+    ("Wrote {}", "{} geschrieben"),
+    ("Could not write {}: {}", "{} konnte nicht geschrieben werden: {}"),
+    ("Not a web link, not opened: {}", "Kein Weblink, nicht geöffnet: {}"),
+    ("Could not open {}: {}", "{} konnte nicht geöffnet werden: {}"),
+    ("Could not save recent items: {}", "Zuletzt geöffnete konnten nicht gespeichert werden: {}"),
+    ("Saving attendance, {} present…", "Anwesenheit wird gespeichert, {} anwesend…"),
+    ("Switched to {}, logging in…", "Zu {} gewechselt, Anmeldung läuft…"),
+    ("'{}' no longer exists", "'{}' gibt es nicht mehr"),
+    ("Restoring '{}'", "'{}' wird wiederhergestellt"),
+    ("Could not read {}: {}", "{} konnte nicht gelesen werden: {}"),
+    ("Could not read '{}' as a date", "'{}' ist kein lesbares Datum"),
+    (
+        "Could not save the table setting: {}",
+        "Die Tabelleneinstellung konnte nicht gespeichert werden: {}",
+    ),
+    (
+        "Could not save the wrap setting: {}",
+        "Die Umbrucheinstellung konnte nicht gespeichert werden: {}",
+    ),
+    ("Renumbered {} of {} Tops", "{} von {} Tops neu nummeriert"),
+    ("Renumbered {} of {} Tops ({})", "{} von {} Tops neu nummeriert ({})"),
+    ("Top created with {} of {} Anträge", "Top angelegt mit {} von {} Anträgen"),
+    ("Top created with {} of {} Anträge ({})", "Top angelegt mit {} von {} Anträgen ({})"),
+    ("Required: {} (Ctrl+N/P to jump)", "Pflichtfelder: {} (Ctrl+N/P zum Springen)"),
+    ("No Anträge by \"{}\" in this Sitzung", "Keine Anträge von \"{}\" in dieser Sitzung"),
+    ("No Anträge by \"{}\" in this Sitzung. {}", "Keine Anträge von \"{}\" in dieser Sitzung. {}"),
+    ("{} {}/{} Tops ({} failed)", "{} {}/{} Tops ({} fehlgeschlagen)"),
+    ("Searched", "Durchsucht"),
+    ("Copied", "Kopiert"),
+    ("Loaded", "Geladen"),
+    ("Counted", "Gezählt"),
+    ("Nothing to go back to", "Nichts zum Zurückgehen"),
+    ("Nothing to go forward to", "Nichts zum Vorgehen"),
+    ("Read-only, log in to save", "Nur lesen, zum Speichern anmelden"),
+    // Errors
+    ("{} {} failed: {} — {}", "{} {} fehlgeschlagen: {} — {}"),
+    ("Saving", "Speichern"),
+    ("Request", "Anfrage"),
+    ("Creating", "Anlegen"),
+    ("Loading", "Laden"),
+    ("Merging", "Zusammenführen"),
+    ("Saving the notes", "Speichern der Notizen"),
+    ("Loading the trash", "Laden des Papierkorbs"),
+    ("Creating the Top", "Anlegen des Tops"),
+    ("R to retry", "R zum Wiederholen"),
+    ("check the connection", "Verbindung prüfen"),
+    ("try again", "erneut versuchen"),
+    ("Ctrl+T to log in again", "Ctrl+T zum neu Anmelden"),
+    ("check the server", "Server prüfen"),
+    ("it may have been deleted, reload", "evtl. gelöscht, neu laden"),
+    ("reload and apply your changes again", "neu laden und Änderungen erneut anwenden"),
+    ("check the backend version", "Backend-Version prüfen"),
+    ("fix the input", "Eingabe korrigieren"),
+    ("backend not reachable ({})", "Backend nicht erreichbar ({})"),
+    ("the backend did not answer in time", "das Backend hat nicht rechtzeitig geantwortet"),
+    ("not authorized ({})", "nicht berechtigt ({})"),
+    ("server error ({})", "Serverfehler ({})"),
+    ("not found", "nicht gefunden"),
+    ("changed by someone else", "von jemand anderem geändert"),
+    ("unexpected answer ({})", "unerwartete Antwort ({})"),
+    ("input rejected", "Eingabe abgelehnt"),
+    ("input rejected: {}", "Eingabe abgelehnt: {}"),
+    ("request failed ({})", "Anfrage fehlgeschlagen ({})"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_known_texts_and_passes_the_rest_through() {
+        assert_eq!(tr(Lang::De, "quit"), "beenden");
+        assert_eq!(tr(Lang::En, "quit"), "quit");
+        assert_eq!(
+            tr(Lang::De, "Sitzung \"x\" deleted"),
+            "Sitzung \"x\" deleted"
+        );
+    }
+
+    #[test]
+    fn translates_texts_built_from_a_template() {
+        assert_eq!(
+            tr_message(Lang::De, "Renumbering Tops… 2/5"),
+            "Tops werden neu nummeriert… 2/5"
+        );
+        assert_eq!(
+            tr_message(Lang::De, "⌛ Saving failed: not found — R to retry"),
+            "⌛ Speichern fehlgeschlagen: nicht gefunden — R zum Wiederholen"
+        );
+        assert_eq!(tr_message(Lang::De, "Saved"), "Gespeichert");
+        assert_eq!(
+            tr_message(Lang::En, "Renumbering Tops… 2/5"),
+            "Renumbering Tops… 2/5"
+        );
+        assert_eq!(tr_message(Lang::De, "Finanzen"), "Finanzen");
+    }
+
+    #[test]
+    fn fills_placeholders_in_order() {
+        assert_eq!(
+            fill("Top {} of {} — {}%", &[&4, &11, &36]),
+            "Top 4 of 11 — 36%"
+        );
+        assert_eq!(
+            filled_in("{} of {}", "a of b of c"),
+            Some(vec!["a", "b of c"])
+        );
+        assert_eq!(filled_in("Copied {}", "Saved"), None);
+    }
+
+    #[test]
+    fn translations_keep_the_placeholders() {
+        for (key, translated) in GERMAN {
+            assert_eq!(
+                key.matches("{}").count(),
+                translated.matches("{}").count(),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn every_text_is_translated_once() {
+        let mut keys: Vec<_> = GERMAN.iter().map(|(key, _)| key).collect();
+        keys.sort();
+        let count = keys.len();
+        keys.dedup();
+        assert_eq!(keys.len(), count);
+    }
+}
@@ -0,0 +1,104 @@
+/// One Antrag read from an import file, before it is sent to the backend.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entwurf {
+    pub titel: String,
+    pub begründung: String,
+    pub antragstext: String,
+}
+
+/// Parses Markdown with one Antrag per block, blocks separated by `---`:
+///
+/// ```text
+/// # Titel
+/// ## Begründung
+/// ...
+/// ## Antragstext
+/// ...
+/// ```
+///
+/// Every block yields its own result so a single broken entry does not stop
+/// the rest of the import.
+pub fn parse(text: &str) -> Vec<Result<Entwurf, String>> {
+    let mut blocks = vec![String::new()];
+    for line in text.lines() {
+        if line.trim() == "---" {
+            blocks.push(String::new());
+        } else {
+            let block = blocks.last_mut().unwrap();
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    blocks
+        .iter()
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| parse_block(block))
+        .collect()
+}
+
+fn parse_block(block: &str) -> Result<Entwurf, String> {
+    let mut entwurf = Entwurf::default();
+    let mut section: Option<&mut String> = None;
+    for line in block.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            section = match heading.trim().to_lowercase().as_str() {
+                "begründung" => Some(&mut entwurf.begründung),
+                "antragstext" => Some(&mut entwurf.antragstext),
+                other => return Err(format!("unknown section '{}'", other)),
+            };
+        } else if let Some(titel) = line.strip_prefix("# ") {
+            entwurf.titel = titel.trim().to_string();
+            section = None;
+        } else if let Some(text) = section.as_mut() {
+            text.push_str(line);
+            text.push('\n');
+        } else if !line.trim().is_empty() {
+            return Err(format!("text outside of a section: '{}'", line.trim()));
+        }
+    }
+    entwurf.begründung = entwurf.begründung.trim().to_string();
+    entwurf.antragstext = entwurf.antragstext.trim().to_string();
+    if entwurf.titel.is_empty() {
+        return Err("missing '# Titel'".to_string());
+    }
+    if entwurf.antragstext.is_empty() {
+        return Err(format!("'{}' has no '## Antragstext'", entwurf.titel));
+    }
+    Ok(entwurf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_multiple_blocks() {
+        let text = "# Erster\n## Begründung\nweil\n## Antragstext\nDer FSR möge...\n---\n# Zweiter\n## Antragstext\nmehr\ntext\n";
+        let parsed = parse(text);
+        assert_eq!(
+            parsed,
+            vec![
+                Ok(Entwurf {
+                    titel: "Erster".to_string(),
+                    begründung: "weil".to_string(),
+                    antragstext: "Der FSR möge...".to_string(),
+                }),
+                Ok(Entwurf {
+                    titel: "Zweiter".to_string(),
+                    begründung: String::new(),
+                    antragstext: "mehr\ntext".to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_broken_blocks_individually() {
+        let text = "## Antragstext\nohne titel\n---\n# Gut\n## Antragstext\nok\n---\n# Leer\n";
+        let parsed = parse(text);
+        assert_eq!(parsed.len(), 3);
+        assert!(parsed[0].is_err());
+        assert!(parsed[1].is_ok());
+        assert!(parsed[2].is_err());
+    }
+}
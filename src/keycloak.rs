@@ -1,18 +1,17 @@
-use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
 
+use anyhow::{anyhow, Context};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use oauth2::basic::BasicClient;
 use oauth2::reqwest::async_http_client;
 use oauth2::AccessToken;
-use oauth2::AuthorizationCode;
 use oauth2::ClientId;
 use oauth2::ClientSecret;
-use oauth2::CsrfToken;
-use oauth2::HttpRequest;
-use oauth2::PkceCodeChallenge;
 use oauth2::RedirectUrl;
 use oauth2::Scope;
 use oauth2::TokenResponse;
-use serde_json::json;
 
 #[derive(serde::Deserialize, serde::Serialize, Debug)]
 pub struct KeycloakConfig {
@@ -22,29 +21,46 @@ pub struct KeycloakConfig {
     pub password: String,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug)]
-struct KeycloakUser {
-    id: String,
-    username: String,
-    email: Option<String>,
-    first_name: Option<String>,
-    last_name: Option<String>,
-    enabled: bool,
-}
-
 struct KeycloakClient {
     token: AccessToken,
+    expires_at: Option<Instant>,
+}
+
+pub struct Token {
+    pub secret: String,
+    /// Taken from `expires_in` of the token response, if Keycloak sent one.
+    pub expires_at: Option<Instant>,
+}
+
+/// How long a login may take before it is given up.
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The login was cancelled by the user, as opposed to failing.
+#[derive(Debug)]
+pub struct Aborted;
+
+impl std::fmt::Display for Aborted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "login aborted")
+    }
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
-struct KeycloakRole {
-    id: String,
-    name: String,
+impl std::error::Error for Aborted {}
+
+/// Like `get_token`, but fails after `timeout` and with `Aborted` once
+/// `cancel` completes, so a stalled login server cannot hang the caller.
+pub async fn login(timeout: Duration, cancel: impl Future<Output = ()>) -> anyhow::Result<Token> {
+    tokio::select! {
+        token = tokio::time::timeout(timeout, get_token()) => token.map_err(|_| {
+            anyhow!("the login server did not answer within {}s", timeout.as_secs())
+        })?,
+        () = cancel => Err(Aborted.into()),
+    }
 }
 
-pub async fn get_token() -> anyhow::Result<String> {
-    let username_env = std::env::var("USERNAME").expect("No USERNAME set");
-    let password_env = std::env::var("PASSWORD").expect("No PASSWORD set");
+pub async fn get_token() -> anyhow::Result<Token> {
+    let username_env = std::env::var("USERNAME").context("No USERNAME set")?;
+    let password_env = std::env::var("PASSWORD").context("No PASSWORD set")?;
     let keycloak_config = KeycloakConfig {
         url: "https://login.inphima.de/auth".to_string(),
         realm: "FSCS-Intern".to_string(),
@@ -59,7 +75,22 @@ pub async fn get_token() -> anyhow::Result<String> {
     )
     .await?;
 
-    Ok(client.token.secret().to_string())
+    Ok(Token {
+        secret: client.token.secret().to_string(),
+        expires_at: client.expires_at,
+    })
+}
+
+/// The user an access token was issued to, its `preferred_username` or
+/// else its `sub` claim. `None` for tokens that are not a JWT.
+pub fn user(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
+    ["preferred_username", "sub"]
+        .iter()
+        .find_map(|claim| claims.get(claim)?.as_str())
+        .map(str::to_string)
 }
 
 impl KeycloakClient {
@@ -69,8 +100,8 @@ impl KeycloakClient {
         user: String,
         password: String,
     ) -> anyhow::Result<Self> {
-        let client_id = std::env::var("CLIENT_ID").expect("No CLIENT ID set");
-        let client_secret = std::env::var("CLIENT_SECRET").expect("No CLIENT SECRET set");
+        let client_id = std::env::var("CLIENT_ID").context("No CLIENT ID set")?;
+        let client_secret = std::env::var("CLIENT_SECRET").context("No CLIENT SECRET set")?;
 
         let client = BasicClient::new(
             ClientId::new(client_id),
@@ -90,17 +121,49 @@ impl KeycloakClient {
         )
         .set_redirect_uri(RedirectUrl::new("http://localhost:8080/".to_string())?);
 
-        let token = client
+        let requested = Instant::now();
+        let response = client
             .exchange_password(
                 &oauth2::ResourceOwnerUsername::new(user.clone()),
                 &oauth2::ResourceOwnerPassword::new(password.clone()),
             )
             .add_scope(Scope::new("openid".to_string()))
             .request_async(async_http_client)
-            .await?
-            .access_token()
-            .clone();
+            .await?;
+
+        Ok(KeycloakClient {
+            token: response.access_token().clone(),
+            expires_at: response
+                .expires_in()
+                .map(|expires_in| requested + expires_in),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(KeycloakClient { token })
+    #[test]
+    fn user_is_read_from_the_token_claims() {
+        let token = |claims: &str| {
+            format!(
+                "eyJhbGciOiJSUzI1NiJ9.{}.c2ln",
+                URL_SAFE_NO_PAD.encode(claims)
+            )
+        };
+        assert_eq!(
+            user(&token(
+                r#"{"sub":"f81d4fae","preferred_username":"mmuster"}"#
+            ))
+            .as_deref(),
+            Some("mmuster")
+        );
+        assert_eq!(
+            user(&token(r#"{"sub":"f81d4fae"}"#)).as_deref(),
+            Some("f81d4fae")
+        );
+        assert_eq!(user(&token("{}")), None);
+        assert_eq!(user("opaque-api-key"), None);
     }
 }
@@ -1,31 +1,99 @@
-use chrono::NaiveDateTime;
+use backend::{Agenda, MergeReport, Mutation, Reload, Request, Response};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
 use color_eyre::config::HookBuilder;
+use config::AntragColumn;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     terminal::{
-        self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
     },
     ExecutableCommand,
 };
-use oauth2::http::header;
+use error::AppError;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use i18n::{fill, tr, tr_message, Lang};
 use ratatui::{prelude::*, style::palette::tailwind, widgets::*};
-use serde::{Deserialize, Serialize};
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
 use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
     error::Error,
     io::{self, stdout},
-    time::Duration,
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
     vec,
 };
-use tui_textarea::TextArea;
+use tui_textarea::{CursorMove, TextArea};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use uuid::Uuid;
 
-const TODO_HEADER_BG: Color = tailwind::BLUE.c950;
-const NORMAL_ROW_COLOR: Color = tailwind::SLATE.c950;
-const SELECTED_STYLE_FG: Color = tailwind::BLUE.c300;
-const TEXT_COLOR: Color = tailwind::SLATE.c200;
+/// Shown in the header and as the terminal window title.
+const APP_TITLE: &str = "Sitzungsverwaltung";
 const URL: &str = "https://new.hhu-fscs.de/";
+/// Where the web frontend shows a Sitzung, see `Config::agenda_url`.
+const AGENDA_URL: &str = "{base}topmanager/sitzung/{id}/";
+
+/// Empty while the production backend is in use.
+static BASE_URL: RwLock<String> = RwLock::new(String::new());
+
+/// The backend in use, `URL` unless the config points somewhere else.
+fn base_url() -> String {
+    let url = BASE_URL.read().unwrap();
+    if url.is_empty() {
+        URL.to_string()
+    } else {
+        url.clone()
+    }
+}
+
+/// Points all requests at the backend from the config, if it names one.
+fn set_base_url(config: &config::Config) {
+    point_at(config.url.as_deref());
+}
+
+/// Points all requests at `url`, or at the production backend for `None`.
+fn point_at(url: Option<&str>) {
+    let url = url.map_or(String::new(), |url| {
+        format!("{}/", url.trim_end_matches('/'))
+    });
+    *BASE_URL.write().unwrap() = url;
+}
+const TICK_RATE: Duration = Duration::from_millis(100);
+const PREFETCH_DEBOUNCE: Duration = Duration::from_millis(300);
+const JUMP_TIMEOUT: Duration = Duration::from_secs(1);
+/// Idle time after which the typed name prefix is dropped.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1500);
+const STATUS_TIMEOUT: Duration = Duration::from_secs(5);
+const PENDING_KEY_TIMEOUT: Duration = Duration::from_secs(1);
+const NOTES_AUTOSAVE: Duration = Duration::from_secs(2);
+/// A held navigation key moves at most one step per this interval, repeats
+/// arriving faster are dropped.
+const NAV_REPEAT_INTERVAL: Duration = Duration::from_millis(40);
+/// How many entries the action log keeps.
+const ACTION_LOG_LEN: usize = 200;
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+/// Backend versions this client was tested against, the upper bound is
+/// exclusive.
+const COMPATIBLE_BACKEND: std::ops::Range<(u64, u64, u64)> = (1, 0, 0)..(2, 0, 0);
 
+mod backend;
+mod config;
+mod crash;
+mod datum;
+mod error;
+mod export;
+mod i18n;
+mod import;
 mod keycloak;
+mod pdf;
+mod session;
 
 #[derive(Debug, Clone)]
 struct StatefulList<T> {
@@ -41,60 +109,741 @@ enum SelectedLayout {
     Anträge,
 }
 
+/// Fields the backend refuses to do without, per kind of item. The editor
+/// marks them and will not save while one is empty, creating or editing.
+const REQUIRED_FIELDS: &[(SelectedLayout, &[&str])] = &[
+    (SelectedLayout::Sitzungen, &["datum", "name"]),
+    (SelectedLayout::Tops, &["titel"]),
+    (SelectedLayout::Anträge, &["titel"]),
+];
+
+/// Smooths out held navigation keys. Terminals deliver key repeats in
+/// bursts, especially while a frame is being drawn, and acting on all of
+/// them overshoots.
+#[derive(Default)]
+struct KeyRepeat {
+    /// The last navigation key acted on.
+    last: Option<(KeyCode, Instant)>,
+}
+
+impl KeyRepeat {
+    /// Whether to act on `code` pressed at `now`.
+    fn accept(&mut self, code: KeyCode, now: Instant) -> bool {
+        if let Some((last, at)) = self.last {
+            if last == code && now.duration_since(at) < NAV_REPEAT_INTERVAL {
+                return false;
+            }
+        }
+        self.last = Some((code, now));
+        true
+    }
+}
+
+fn is_navigation(key: &KeyEvent) -> bool {
+    use KeyCode::*;
+    key.modifiers.is_empty()
+        && matches!(
+            key.code,
+            Char('j') | Char('k') | Down | Up | PageDown | PageUp
+        )
+}
+
+/// The `J` popup with an item as the backend returned it.
+struct RawJson {
+    url: String,
+    /// `None` while it is being fetched.
+    text: Option<String>,
+    scroll: u16,
+}
+
+/// A `Ctrl+S` save waiting for the backend.
+struct Saving {
+    url: String,
+    /// The saved item, several PATCHes may go to the same URL.
+    id: Uuid,
+    /// The params that were sent, they count as saved once it went through.
+    params: Vec<Param>,
+}
+
+impl Saving {
+    fn is(&self, mutation: &Mutation) -> bool {
+        mutation.method == Method::PATCH
+            && mutation.url == self.url
+            && mutation
+                .body
+                .as_ref()
+                .and_then(|body| body["id"].as_str())
+                .is_some_and(|id| id == self.id.to_string())
+    }
+}
+
+/// The `r` view of the whole protocol of the current Sitzung.
+struct Protocol {
+    sitzung: Uuid,
+    /// `None` while the Tops and Anträge are being fetched.
+    agenda: Option<Result<Agenda, String>>,
+    scroll: u16,
+}
+
+/// A foldable part of the Antrag details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Metadaten,
+    Begründung,
+    Antragstext,
+    Anhänge,
+}
+
+impl Section {
+    fn title(self) -> &'static str {
+        match self {
+            Section::Metadaten => "Metadaten",
+            Section::Begründung => "Begründung",
+            Section::Antragstext => "Antragstext",
+            Section::Anhänge => "Anhänge",
+        }
+    }
+}
+
+/// Which sections of the Antrag details are folded away.
+#[derive(Debug, Clone, Copy, Default)]
+struct Collapsed {
+    metadaten: bool,
+    begründung: bool,
+    antragstext: bool,
+    anhänge: bool,
+}
+
+impl Collapsed {
+    fn get_mut(&mut self, section: Section) -> &mut bool {
+        match section {
+            Section::Metadaten => &mut self.metadaten,
+            Section::Begründung => &mut self.begründung,
+            Section::Antragstext => &mut self.antragstext,
+            Section::Anhänge => &mut self.anhänge,
+        }
+    }
+
+    fn get(mut self, section: Section) -> bool {
+        *self.get_mut(section)
+    }
+
+    /// Collapses everything unless it already is, then expands everything.
+    fn toggle_all(&mut self) {
+        let all = self.metadaten && self.begründung && self.antragstext && self.anhänge;
+        *self = if all {
+            Collapsed::default()
+        } else {
+            Collapsed {
+                metadaten: true,
+                begründung: true,
+                antragstext: true,
+                anhänge: true,
+            }
+        };
+    }
+}
+
+/// Which side of the Anträge view `j`/`k` act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    List,
+    Detail,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 struct Sitzung {
+    #[serde(default)]
     name: String,
     datum: NaiveDateTime,
     id: Uuid,
+    /// Names of the members who were present.
+    #[serde(default)]
+    anwesend: Vec<String>,
+    /// Opaque version from the backend, sent back with edits so it can
+    /// refuse ones based on an outdated state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 struct Top {
+    #[serde(default)]
     name: String,
     id: Uuid,
+    #[serde(default)]
     inhalt: serde_json::Value,
+    #[serde(default)]
     weight: i32,
+    /// Minutes taken during the meeting.
+    #[serde(default)]
+    protokoll: String,
+    /// Who presents the Top.
+    #[serde(default)]
+    verantwortlich: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sitzung_id: Option<Uuid>,
+    /// See `Sitzung::updated_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 struct Antrag {
     id: Uuid,
+    #[serde(default)]
     titel: String,
+    #[serde(default)]
     begründung: String,
+    #[serde(default)]
     antragstext: String,
+    #[serde(default)]
+    status: AntragStatus,
+    #[serde(default)]
+    anhänge: Vec<String>,
+    /// The kind of Antrag, e.g. "Finanzantrag".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    top_id: Option<Uuid>,
+    #[serde(
+        default,
+        alias = "autor",
+        alias = "antragssteller",
+        skip_serializing_if = "Option::is_none"
+    )]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    /// See `Sitzung::updated_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<String>,
+    /// Who saved the last change, if the backend records it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified_by: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum AntragStatus {
+    #[default]
+    Eingereicht,
+    Angenommen,
+    Abgelehnt,
+    Zurückgezogen,
+}
+
+impl AntragStatus {
+    fn next(self) -> Self {
+        match self {
+            AntragStatus::Eingereicht => AntragStatus::Angenommen,
+            AntragStatus::Angenommen => AntragStatus::Abgelehnt,
+            AntragStatus::Abgelehnt => AntragStatus::Zurückgezogen,
+            AntragStatus::Zurückgezogen => AntragStatus::Eingereicht,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AntragStatus::Eingereicht => "eingereicht",
+            AntragStatus::Angenommen => "angenommen",
+            AntragStatus::Abgelehnt => "abgelehnt",
+            AntragStatus::Zurückgezogen => "zurückgezogen",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            AntragStatus::Eingereicht => tailwind::BLUE.c400,
+            AntragStatus::Angenommen => tailwind::GREEN.c500,
+            AntragStatus::Abgelehnt => tailwind::RED.c500,
+            AntragStatus::Zurückgezogen => tailwind::SLATE.c500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Datum,
+    Name,
+    Weight,
+    Titel,
+    Status,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sort {
+    key: SortKey,
+    descending: bool,
+}
+
+impl Sort {
+    fn apply(self, ordering: Ordering) -> Ordering {
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    fn label(self) -> String {
+        let arrow = if self.descending { "↓" } else { "↑" };
+        format!("{:?} {}", self.key, arrow)
+    }
+}
+
+impl SelectedLayout {
+    fn sort_keys(&self) -> &'static [SortKey] {
+        match self {
+            SelectedLayout::Sitzungen => &[SortKey::Datum, SortKey::Name],
+            SelectedLayout::Tops => &[SortKey::Weight, SortKey::Name],
+            SelectedLayout::Anträge => &[SortKey::Titel, SortKey::Status],
+        }
+    }
+}
+
+/// Pinned Sitzungen come first, then upcoming ones, then past ones. The sort
+/// only applies within each group. By Datum, descending means closest to
+/// `now` first: the next Sitzung leads the upcoming ones, the last one the
+/// past ones.
+fn compare_sitzungen(
+    sort: Sort,
+    now: NaiveDateTime,
+    pinned: &HashSet<Uuid>,
+) -> impl Fn(&Sitzung, &Sitzung) -> Ordering + '_ {
+    move |a: &Sitzung, b: &Sitzung| {
+        let past = a.datum < now;
+        (!pinned.contains(&a.id))
+            .cmp(&!pinned.contains(&b.id))
+            .then(past.cmp(&(b.datum < now)))
+            .then_with(|| {
+                sort.apply(match sort.key {
+                    SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                    _ if past => a.datum.cmp(&b.datum),
+                    _ => b.datum.cmp(&a.datum),
+                })
+            })
+    }
+}
+
+fn compare_tops(sort: Sort) -> impl Fn(&Top, &Top) -> Ordering {
+    move |a: &Top, b: &Top| {
+        sort.apply(match sort.key {
+            SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            _ => a.weight.cmp(&b.weight),
+        })
+    }
+}
+
+fn compare_anträge(sort: Sort) -> impl Fn(&Antrag, &Antrag) -> Ordering {
+    move |a: &Antrag, b: &Antrag| {
+        sort.apply(match sort.key {
+            SortKey::Status => a.status.cmp(&b.status),
+            _ => a.titel.to_lowercase().cmp(&b.titel.to_lowercase()),
+        })
+    }
+}
+
+/// A soft-deleted Sitzung, Top or Antrag as the backend lists it with
+/// `?deleted=true`.
+#[derive(Deserialize, Debug, Clone)]
+struct Deleted {
+    id: Uuid,
+    #[serde(default, alias = "titel")]
+    name: String,
+    #[serde(default)]
+    deleted_at: Option<NaiveDateTime>,
+}
+
+/// The `/` filter of one list. Tops and Anträge keep their full list here
+/// while the view only shows the matches, Sitzungen are filtered from
+/// `all_sitzungen` anyway.
+struct Filter<'a> {
+    input: TextArea<'a>,
+    /// Keys go to the input until Enter.
+    typing: bool,
+    unfiltered: Unfiltered,
+}
+
+enum Unfiltered {
+    Sitzungen,
+    Tops(Vec<Top>),
+    Anträge(Vec<Antrag>),
+}
+
+impl Filter<'_> {
+    fn query(&self) -> String {
+        self.input.lines().join(" ")
+    }
+
+    fn layout(&self) -> SelectedLayout {
+        match self.unfiltered {
+            Unfiltered::Sitzungen => SelectedLayout::Sitzungen,
+            Unfiltered::Tops(_) => SelectedLayout::Tops,
+            Unfiltered::Anträge(_) => SelectedLayout::Anträge,
+        }
+    }
+}
+
+/// Keeps the items whose text fuzzily matches `query`, best matches first.
+/// Items with the same score keep their order.
+fn fuzzy_rank<T>(items: Vec<T>, query: &str, text: impl Fn(&T) -> &str) -> Vec<T> {
+    if query.is_empty() {
+        return items;
+    }
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, T)> = items
+        .into_iter()
+        .filter_map(|item| Some((matcher.fuzzy_match(text(&item), query)?, item)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Turns the output of `fit_to_width` into lines with the characters at
+/// `matched` emphasized. The indices count characters of the full text, so
+/// only the first line, which is a prefix of it, gets emphasized.
+fn emphasize_matches(lines: Vec<String>, matched: &[usize]) -> Vec<Line<'static>> {
+    let emphasized = Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut lines = lines.into_iter();
+    let first = lines.next().unwrap_or_default();
+    let spans: Vec<Span> = first
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if matched.contains(&i) {
+                Span::styled(c.to_string(), emphasized)
+            } else {
+                Span::raw(c.to_string())
+            }
+        })
+        .collect();
+    std::iter::once(Line::from(spans))
+        .chain(lines.map(Line::from))
+        .collect()
+}
+
+/// The notes mode for the protokoll of one Top.
+struct Notes<'a> {
+    top: Uuid,
+    input: TextArea<'a>,
+    /// When the text was last changed, cleared once a save is sent.
+    changed: Option<Instant>,
+    saving: bool,
+    error: Option<String>,
+}
+
+impl Notes<'_> {
+    fn state(&self) -> String {
+        match (&self.error, self.changed, self.saving) {
+            (Some(err), _, _) => format!("saving failed: {}", err),
+            (None, Some(_), _) => "unsaved".to_string(),
+            (None, None, true) => "saving…".to_string(),
+            (None, None, false) => "saved".to_string(),
+        }
+    }
+}
+
+/// Looking for the Anträge of one author in the open Sitzung.
+enum AuthorSearch<'a> {
+    Prompt(Box<TextArea<'a>>),
+    Searching(String),
+    Results(String, StatefulList<(Top, Antrag)>),
+}
+
+/// The overview shown before drilling into the Sitzungen.
+#[derive(Default)]
+struct Dashboard {
+    stats: Option<Result<backend::Stats, String>>,
+    loading: bool,
+}
+
+/// A place in the app, as remembered by the back/forward history.
+#[derive(Debug, Clone)]
+struct View {
+    layout: SelectedLayout,
+    /// The open Sitzung and Top, `None` in the Sitzungen view.
+    visit: Option<session::Visit>,
+    selected: Option<Uuid>,
+}
+
+impl View {
+    fn same_place(&self, other: &View) -> bool {
+        self.layout == other.layout && self.visit == other.visit
+    }
+}
+
+/// Places left behind, like the history of a browser.
+#[derive(Debug)]
+struct History<T> {
+    back: Vec<T>,
+    forward: Vec<T>,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        History {
+            back: vec![],
+            forward: vec![],
+        }
+    }
+}
+
+impl<T> History<T> {
+    /// Records `left` as the place before a new one, which drops the places
+    /// that were ahead.
+    fn push(&mut self, left: T) {
+        self.back.push(left);
+        self.forward.clear();
+    }
+
+    fn back(&mut self, current: T) -> Option<T> {
+        let view = self.back.pop()?;
+        self.forward.push(current);
+        Some(view)
+    }
+
+    fn forward(&mut self, current: T) -> Option<T> {
+        let view = self.forward.pop()?;
+        self.back.push(current);
+        Some(view)
+    }
+}
+
+/// The attendance checklist of one Sitzung.
+struct Attendance {
+    sitzung: Uuid,
+    version: Option<String>,
+    members: StatefulList<(String, bool)>,
+}
+
+/// The trash of the view it was opened from.
+struct Trash {
+    layout: SelectedLayout,
+    url: String,
+    items: StatefulList<Deleted>,
+    loading: bool,
+}
+
+enum ImportStep<'a> {
+    Prompt(Box<TextArea<'a>>),
+    Confirm(Vec<Result<import::Entwurf, String>>),
+    Running {
+        /// Counts only the entries that could be read, those are sent.
+        done: usize,
+        total: usize,
+        titel: String,
+        cancel: Arc<AtomicBool>,
+        /// One line per entry left out because it could not be read.
+        unreadable: Vec<String>,
+    },
+    Report(String),
+}
+
+enum MergeStep {
+    /// Picking the Sitzung the Tops of `source` are moved to.
+    Target {
+        source: Sitzung,
+        targets: StatefulList<Sitzung>,
+    },
+    Confirm {
+        source: Sitzung,
+        target: Sitzung,
+    },
+    Running {
+        source: Sitzung,
+        done: usize,
+        total: usize,
+        titel: String,
+    },
+    /// `empty` holds the source once all of its Tops were moved, so it
+    /// can be deleted.
+    Report {
+        report: String,
+        empty: Option<Sitzung>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Param {
+    /// Label shown in the editor.
     titel: String,
+    /// Field name in the JSON sent to the backend.
+    key: String,
     text: String,
 }
 
-fn get_sitzungen() -> Vec<Sitzung> {
-    let endoint = "api/topmanager/sitzungen/";
-    let reqwest = reqwest::blocking::Client::new();
-    let response = reqwest.get(URL.to_string() + endoint).send().unwrap();
-    let sitzungen: Vec<Sitzung> = response.json().unwrap();
-    sitzungen
+impl Param {
+    fn new(titel: &str, key: &str, text: impl Into<String>) -> Self {
+        Param {
+            titel: titel.to_string(),
+            key: key.to_string(),
+            text: text.into(),
+        }
+    }
+}
+
+/// A key and what it does, as listed in the footer.
+type Hint = (&'static str, &'static str);
+
+const SITZUNGEN_HINTS: &[Hint] = &[
+    ("↓↑", "move"),
+    ("/", "filter"),
+    ("o", "open"),
+    ("p", "new"),
+    ("e", "edit"),
+    ("E/F2", "rename"),
+    ("J", "raw JSON"),
+    ("dd", "delete"),
+    ("yy", "copy as JSON"),
+    ("i", "copy id"),
+    ("s/S", "sort key/order"),
+    ("P", "show/hide past"),
+    ("f", "pin"),
+    ("L", "copy link"),
+    ("a", "attendance"),
+    ("D", "dashboard"),
+    ("z", "compact"),
+    ("T", "today"),
+    ("t", "trash"),
+    ("M", "merge into"),
+    ("q", "quit"),
+];
+
+const TOPS_HINTS: &[Hint] = &[
+    ("↓↑", "move"),
+    ("/", "filter"),
+    ("o", "open"),
+    ("p", "new"),
+    ("P", "quick new"),
+    ("e", "edit"),
+    ("E/F2", "rename"),
+    ("J", "raw JSON"),
+    ("dd", "delete"),
+    ("yy", "copy as JSON"),
+    ("ym", "copy as Markdown"),
+    ("i", "copy id"),
+    ("A", "Anträge by author"),
+    ("[/]", "previous/next Sitzung"),
+    ("n", "notes"),
+    ("r", "read protocol"),
+    ("v", "agenda one-liner"),
+    ("L", "copy link"),
+    ("u", "only open"),
+    ("W", "renumber weights"),
+    ("z", "compact"),
+    ("s/S", "sort key/order"),
+    ("t", "trash"),
+    ("q", "back"),
+    ("H", "Sitzungen"),
+];
+
+const ANTRÄGE_HINTS: &[Hint] = &[
+    ("↓↑", "move"),
+    ("/", "filter"),
+    ("p", "new"),
+    ("P", "quick new"),
+    ("c", "duplicate"),
+    ("e", "edit"),
+    ("E/F2", "rename"),
+    ("J", "raw JSON"),
+    ("v", "status"),
+    ("a/x", "angenommen/abgelehnt"),
+    ("dd", "delete"),
+    ("yy", "copy as JSON"),
+    ("ym", "copy as Markdown"),
+    ("i", "copy id"),
+    ("O", "attachment"),
+    ("I", "import"),
+    ("X", "export PDF"),
+    ("g", "show parents"),
+    ("Tab", "focus details"),
+    ("z", "compact"),
+    ("[/]", "previous/next Top"),
+    ("s/S", "sort key/order"),
+    ("T", "table/list"),
+    ("t", "trash"),
+    ("q", "back"),
+    ("H", "Sitzungen"),
+];
+
+const EDIT_HINTS: &[Hint] = &[
+    ("↓↑", "move"),
+    ("e", "edit value"),
+    ("P", "preview payload"),
+    ("D", "show changes"),
+    ("T", "reset to template"),
+    ("Ctrl+N/P", "next/previous missing"),
+    ("Ctrl+S", "save"),
+    ("q", "close"),
+];
+
+const CREATE_HINTS: &[Hint] = &[
+    ("↓↑", "move"),
+    ("e", "edit value"),
+    ("P", "preview payload"),
+    ("T", "reset to template"),
+    ("Ctrl+N/P", "next/previous missing"),
+    ("Ctrl+S", "create"),
+    ("q", "close"),
+];
+
+const TRASH_HINTS: &[Hint] = &[("↓↑", "move"), ("r", "restore"), ("q", "close")];
+
+const GLOBAL_HINTS: &[Hint] = &[
+    ("Ctrl+Q", "quit"),
+    ("Alt+←/→", "back/forward"),
+    ("'", "type-ahead"),
+    ("Ctrl+P", "recent"),
+    ("Ctrl+W", "profiles"),
+    ("Ctrl+L", "action log"),
+    ("F", "focus mode"),
+    ("Ctrl+R", "reload config"),
+    ("Ctrl+T", "log in again"),
+    ("Ctrl+G", "language"),
+];
+
+#[derive(Deserialize, Debug)]
+struct Paged<T> {
+    results: Vec<T>,
+    #[allow(dead_code)]
+    count: usize,
 }
 
-fn get_tops(sitzung: Sitzung) -> Vec<Top> {
-    let url = format!("{}api/topmanager/sitzung/{}/tops/", URL, sitzung.id);
-    let reqwest = reqwest::blocking::Client::new();
-    let response = reqwest.get(url).send().unwrap();
-    let tops: Vec<Top> = response.json().unwrap();
-    tops
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ListResponse<T> {
+    Bare(Vec<T>),
+    Paged(Paged<T>),
 }
 
-fn get_anträge(top: Top) -> Vec<Antrag> {
-    let url = format!("{}api/topmanager/tops/{}/anträge/", URL, top.id);
-    let reqwest = reqwest::blocking::Client::new();
-    let response = reqwest.get(url).send().unwrap();
-    let anträge: Vec<Antrag> = response.json().unwrap();
-    anträge
+impl<T> ListResponse<T> {
+    fn into_items(self) -> Vec<T> {
+        match self {
+            ListResponse::Bare(items) => items,
+            ListResponse::Paged(paged) => paged.results,
+        }
+    }
+}
+
+impl ListResponse<serde_json::Value> {
+    /// Deserializes every item on its own, so a single malformed record only
+    /// drops itself. Also returns how many items were dropped.
+    fn parse_items<T: DeserializeOwned>(self) -> (Vec<T>, usize) {
+        let values = self.into_items();
+        let total = values.len();
+        let items: Vec<T> = values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect();
+        let failed = total - items.len();
+        (items, failed)
+    }
 }
 
 struct App<'a> {
+    all_sitzungen: Vec<Sitzung>,
+    show_past: bool,
     sitzungen: StatefulList<Sitzung>,
     tops_selected_sitzung: StatefulList<Top>,
     anträge_selected_top: StatefulList<Antrag>,
@@ -103,25 +852,187 @@ struct App<'a> {
     edit_buffer: StatefulList<Param>,
     currently_creating: Option<SelectedLayout>,
     edit_param_pop: Option<Param>,
+    payload_preview: Option<String>,
+    raw_json: Option<RawJson>,
+    protocol: Option<Protocol>,
+    /// The `v` popup with all Tops on one line.
+    agenda_summary: Option<String>,
+    /// Only the selected item on an otherwise empty screen, toggled with
+    /// `F`. Holds how far its text is scrolled.
+    focus_mode: Option<u16>,
+    /// The params as loaded when the editor was opened, for the diff view.
+    edit_original: Vec<Param>,
+    /// Asking whether to save before leaving the editor.
+    confirm_leave: bool,
+    /// A `Ctrl+S` save is in flight, its result goes to the status line.
+    saving: Option<Saving>,
+    show_diff: bool,
+    pending_key: Option<(char, Instant)>,
     current_text_area: TextArea<'a>,
     sitzung: Sitzung,
     top: Top,
-    token: String,
+    token: Option<String>,
+    /// When `token` stops being accepted, if known.
+    token_expires: Option<Instant>,
+    /// Who `token` belongs to, sent along with every edit.
+    user: Option<String>,
     antrag: Antrag,
+    tops_cache: HashMap<Uuid, Vec<Top>>,
+    prefetching: HashSet<Uuid>,
+    prefetch_due: Option<(Uuid, Instant)>,
+    /// When the current list was last reloaded by `auto_refresh_secs`.
+    last_auto_refresh: Instant,
+    pending_jump: Option<(String, Instant)>,
+    /// Name prefix typed after `'`, the selection follows the first match.
+    type_ahead: Option<(String, Instant)>,
+    status: Option<(String, Instant)>,
+    /// Set while the status shows a backend error, in the error's color.
+    status_color: Option<Color>,
+    /// The last backend error shown, kept for crash reports.
+    last_error: Option<String>,
+    clipboard: Option<arboard::Clipboard>,
+    verbose: bool,
+    /// TLS certificates are not checked, only ever true for non-production
+    /// backends.
+    insecure: bool,
+    config: config::Config,
+    keymap: config::KeyMap,
+    antrag_counts: HashMap<Uuid, Option<backend::AntragCount>>,
+    /// Only list Tops with Anträge that are still open, toggled with `u`.
+    open_tops_only: bool,
+    counting: HashSet<Uuid>,
+    backend: backend::Backend,
+    /// The Sitzung whose Tops are being loaded and the index to select once
+    /// they arrive.
+    awaiting_tops: Option<(Uuid, usize)>,
+    awaiting_anträge: Option<(Uuid, usize)>,
+    awaiting_sitzungen: bool,
+    /// Since when the awaited lists are loading, and how to cancel them.
+    loading: Option<(Instant, Vec<backend::Cancel>)>,
+    /// Top weights are being rewritten.
+    renumbering: bool,
+    /// The Top or Sitzung being fetched to find the parents of an Antrag.
+    locating: Option<Uuid>,
+    /// A freshly created item to select once it shows up in a reloaded list.
+    select_created: Option<Uuid>,
+    /// Open the editor once `select_created` is found, set by duplicating.
+    edit_created: bool,
+    /// The editor waits for its save to go through before it closes, and
+    /// stays open with the edits if the save fails.
+    leave_after_save: bool,
+    session: session::Session,
+    sitzungen_sort: Sort,
+    tops_sort: Sort,
+    anträge_sort: Sort,
+    import: Option<ImportStep<'a>>,
+    merge: Option<MergeStep>,
+    trash: Option<Trash>,
+    filter: Option<Filter<'a>>,
+    /// The recently opened Sitzungen and Tops, while `Ctrl+P` is open.
+    switcher: Option<StatefulList<session::Visit>>,
+    attendance: Option<Attendance>,
+    history: History<View>,
+    dashboard: Option<Dashboard>,
+    /// Asking whether to save unsaved edits before quitting.
+    confirm_quit: bool,
+    /// Quit once the save sent from `confirm_quit` went through.
+    quit_after_save: bool,
+    quit: bool,
+    /// The backend profile in use, `None` for the default one.
+    profile: Option<String>,
+    /// Shown in the header while the backend runs an untested version.
+    version_warning: Option<String>,
+    /// The profiles to pick from, while `Ctrl+W` is open.
+    profile_switcher: Option<StatefulList<Option<String>>>,
+    /// The Anhänge of the selected Antrag to pick from, while `O` is open.
+    anhang_picker: Option<StatefulList<String>>,
+    author_search: Option<AuthorSearch<'a>>,
+    /// The name typed for a `P` quick-create.
+    quick_create: Option<TextArea<'a>>,
+    /// The new name typed over the selected row.
+    rename: Option<TextArea<'a>>,
+    notes: Option<Notes<'a>>,
+    /// One-line header and footer, one row per item.
+    compact: bool,
+    /// `updated_at` of the item in the editor, as it was when editing began.
+    edit_version: Option<String>,
+    /// The kind, id and params of the last PATCH, to reapply them on conflict.
+    last_patch: Option<(SelectedLayout, Uuid, Vec<Param>)>,
+    /// When the item in the editor was fetched, to warn before saving over
+    /// a stale copy.
+    edit_fetched: Option<Instant>,
+    /// Saving waits for a decision on the stale copy in the editor, `true`
+    /// if the editor is to be left after saving.
+    stale_save: Option<bool>,
+    /// A PATCH was refused because the item changed on the server.
+    conflict: bool,
+    /// Edits to put on top of the server version once it is fetched again.
+    reapply: Option<Vec<Param>>,
+    /// The last mutation that failed, `R` sends it again.
+    last_failed: Option<Request>,
+    focus: Pane,
+    /// Lines the Antrag details are scrolled down by.
+    detail_scroll: u16,
+    collapsed: Collapsed,
+    /// When the last key that loads from the backend was acted on.
+    last_request: Option<Instant>,
+    key_repeat: KeyRepeat,
+    /// The edit-value popup fills the screen, toggled with F11.
+    edit_fullscreen: bool,
+    /// What the app did and how it went, newest last.
+    action_log: VecDeque<(DateTime<Local>, String)>,
+    show_log: bool,
+    /// When such a key was last dropped for coming too fast.
+    throttled: Option<Instant>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("export") {
+        if let Err(err) = export::run(&args[2..]).await {
+            eprintln!("Export failed: {:#}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     init_error_hooks()?;
-    let terminal = init_terminal()?;
+    let verbose = std::env::args().any(|arg| arg == "--verbose");
+    let insecure = std::env::args().any(|arg| arg == "--insecure");
+
+    let mut app = match App::new(verbose, insecure).await {
+        Ok(app) => app,
+        Err(err) if err.is::<keycloak::Aborted>() => {
+            eprintln!("Login aborted.");
+            return Ok(());
+        }
+        Err(err) => {
+            eprintln!("Login failed: {:#}", err);
+            eprintln!(
+                "Make sure USERNAME, PASSWORD, CLIENT_ID and CLIENT_SECRET are set and that the login server is reachable."
+            );
+            if !confirm_read_only()? {
+                return Ok(());
+            }
+            App::with_token(None, verbose, insecure)
+        }
+    };
 
-    App::new().await.run(terminal)?;
+    let terminal = init_terminal()?;
+    app.run(terminal)?;
 
     restore_terminal()?;
 
     Ok(())
 }
 
+fn confirm_read_only() -> io::Result<bool> {
+    eprint!("Continue in read-only mode? [y/N] ");
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
 fn init_error_hooks() -> color_eyre::Result<()> {
     let (panic, error) = HookBuilder::default().into_hooks();
     let panic = panic.into_panic_hook();
@@ -133,6 +1044,10 @@ fn init_error_hooks() -> color_eyre::Result<()> {
     std::panic::set_hook(Box::new(move |info| {
         let _ = restore_terminal();
         panic(info);
+        if let Some(path) = crash::write_report(&info.to_string()) {
+            eprintln!("A crash report was written to {}", path.display());
+            eprintln!("Please attach it when reporting the bug.");
+        }
     }));
     Ok(())
 }
@@ -140,6 +1055,7 @@ fn init_error_hooks() -> color_eyre::Result<()> {
 fn init_terminal() -> color_eyre::Result<Terminal<impl Backend>> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(SetTitle(APP_TITLE))?;
     let backend = CrosstermBackend::new(stdout());
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -151,10 +1067,51 @@ fn restore_terminal() -> color_eyre::Result<()> {
     Ok(())
 }
 
-impl<'a> App<'_> {
-    async fn new() -> Self {
-        Self {
-            sitzungen: StatefulList::with_items(get_sitzungen()),
+impl App<'_> {
+    async fn new(verbose: bool, insecure: bool) -> anyhow::Result<Self> {
+        eprintln!("Logging in… (Ctrl+C to abort)");
+        let ctrl_c = async {
+            // Without a signal handler only the timeout ends a stalled login.
+            if tokio::signal::ctrl_c().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        let token = keycloak::login(keycloak::LOGIN_TIMEOUT, ctrl_c).await?;
+        let mut app = Self::with_token(Some(token.secret), verbose, insecure);
+        app.token_expires = token.expires_at;
+        app.user = app.token.as_deref().and_then(keycloak::user);
+        Ok(app)
+    }
+
+    fn with_token(token: Option<String>, verbose: bool, insecure: bool) -> Self {
+        let (config, keymap, mut status) = match config::load() {
+            Ok((config, keymap)) => (config, keymap, None),
+            Err(err) => (
+                config::Config::default(),
+                config::KeyMap::default(),
+                Some((format!("Config error: {:#}", err), Instant::now())),
+            ),
+        };
+        let session = session::load().unwrap_or_else(|err| {
+            status = Some((format!("Session error: {:#}", err), Instant::now()));
+            session::Session::default()
+        });
+        set_base_url(&config);
+        let mut insecure = insecure || config.insecure;
+        if insecure && base_url() == URL {
+            insecure = false;
+            status = Some((
+                "Ignoring --insecure for the production backend".to_string(),
+                Instant::now(),
+            ));
+        }
+        let backend = backend::Backend::spawn(base_url(), insecure, config.auth.clone());
+        let compact = config.compact;
+        let dashboard = config.dashboard;
+        let mut app = Self {
+            all_sitzungen: vec![],
+            show_past: false,
+            sitzungen: StatefulList::with_items(vec![]),
             tops_selected_sitzung: StatefulList::with_items(vec![]),
             anträge_selected_top: StatefulList::with_items(vec![]),
             layout: SelectedLayout::Sitzungen,
@@ -162,683 +1119,5904 @@ impl<'a> App<'_> {
             currently_creating: None,
             edit_buffer: StatefulList::with_items(vec![]),
             edit_param_pop: None,
+            payload_preview: None,
+            raw_json: None,
+            protocol: None,
+            agenda_summary: None,
+            focus_mode: None,
+            edit_original: vec![],
+            confirm_leave: false,
+            saving: None,
+            show_diff: false,
+            pending_key: None,
             current_text_area: TextArea::default(),
             sitzung: Sitzung::default(),
             top: Top::default(),
-            token: keycloak::get_token().await.unwrap(),
+            token,
             antrag: Antrag::default(),
-        }
+            tops_cache: HashMap::new(),
+            prefetching: HashSet::new(),
+            prefetch_due: None,
+            last_auto_refresh: Instant::now(),
+            pending_jump: None,
+            type_ahead: None,
+            status,
+            status_color: None,
+            last_error: None,
+            clipboard: None,
+            verbose,
+            insecure,
+            config,
+            keymap,
+            antrag_counts: HashMap::new(),
+            open_tops_only: false,
+            counting: HashSet::new(),
+            backend,
+            awaiting_tops: None,
+            awaiting_anträge: None,
+            awaiting_sitzungen: false,
+            loading: None,
+            locating: None,
+            renumbering: false,
+            select_created: None,
+            edit_created: false,
+            leave_after_save: false,
+            session,
+            sitzungen_sort: Sort {
+                key: SortKey::Datum,
+                descending: true,
+            },
+            tops_sort: Sort {
+                key: SortKey::Weight,
+                descending: false,
+            },
+            anträge_sort: Sort {
+                key: SortKey::Titel,
+                descending: false,
+            },
+            import: None,
+            merge: None,
+            trash: None,
+            filter: None,
+            switcher: None,
+            attendance: None,
+            quick_create: None,
+            rename: None,
+            history: History::default(),
+            dashboard: dashboard.then(Dashboard::default),
+            author_search: None,
+            confirm_quit: false,
+            quit_after_save: false,
+            quit: false,
+            token_expires: None,
+            user: None,
+            profile: None,
+            profile_switcher: None,
+            anhang_picker: None,
+            version_warning: None,
+            notes: None,
+            last_failed: None,
+            compact,
+            edit_version: None,
+            last_patch: None,
+            edit_fetched: None,
+            stale_save: None,
+            conflict: false,
+            reapply: None,
+            focus: Pane::List,
+            detail_scroll: 0,
+            collapsed: Collapsed::default(),
+            last_request: None,
+            key_repeat: KeyRepeat::default(),
+            edit_fullscreen: false,
+            action_log: VecDeque::new(),
+            show_log: false,
+            throttled: None,
+        };
+        app.backend.send(Request::Version);
+        app.load_sitzungen();
+        app
     }
 
-    fn get_sitzungen(&mut self) {
-        let endoint = "api/topmanager/sitzungen/";
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(URL.to_string() + endoint).send().unwrap();
-        let sitzungen: Vec<Sitzung> = response.json().unwrap();
-        self.sitzungen = StatefulList::with_items(sitzungen);
+    fn read_only(&self) -> bool {
+        self.token.is_none()
     }
 
-    fn open_sitzung(&mut self) {
-        let selected = self.sitzungen.state.selected().unwrap();
-        let sitzung = self.sitzungen.items[selected].clone();
-        let url = format!("{}api/topmanager/sitzung/{}/tops/", URL, sitzung.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(url).send().unwrap();
-        let tops: Vec<Top> = response.json().unwrap();
-        self.sitzung = sitzung;
-        self.tops_selected_sitzung = StatefulList::with_items(tops);
-        //open new view with sitzung
-        self.layout = SelectedLayout::Tops;
+    fn set_sitzungen(&mut self, sitzungen: Vec<Sitzung>) {
+        let selected = self.sitzungen.state.selected().unwrap_or(0);
+        self.all_sitzungen = sitzungen;
+        self.sitzungen = StatefulList::with_items(self.visible_sitzungen());
+        self.sitzungen.select_nearest(selected);
+        if self
+            .dashboard
+            .as_ref()
+            .is_some_and(|dashboard| dashboard.stats.is_none() && !dashboard.loading)
+        {
+            self.load_stats();
+        }
     }
 
-    fn create_sitzung(&mut self) {
-        self.edit_buffer.items.push(Param {
-            titel: "Datum".to_string(),
-            text: "".to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Name".to_string(),
-            text: "".to_string(),
-        });
-        self.currently_creating = Some(SelectedLayout::Sitzungen);
+    fn handle_author_prompt_key(&mut self, key: KeyEvent) {
+        let Some(AuthorSearch::Prompt(input)) = &mut self.author_search else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.author_search = None,
+            KeyCode::Enter => {
+                let author = input.lines().join(" ").trim().to_string();
+                if author.is_empty() {
+                    self.author_search = None;
+                    return;
+                }
+                self.author_search = Some(AuthorSearch::Searching(author));
+                self.backend.send(Request::Agenda(self.sitzung.id));
+            }
+            _ => {
+                input.input(key);
+            }
+        }
     }
 
-    fn delete_sitzung(&mut self) {
-        let token = self.token.clone();
-        let cookie = format!("access_token={}", token);
-        let selected = self.sitzungen.state.selected().unwrap();
-        let sitzung = self.sitzungen.items[selected].clone();
-        let url = format!("{}api/topmanager/sitzung/", URL);
-        let reqwest = reqwest::blocking::Client::new();
-        let json = serde_json::json!({ "id": sitzung.id });
-        let response = reqwest
-            .delete(url)
-            .header("Cookie", cookie)
-            .json(&json)
-            .send()
-            .unwrap();
-        self.get_sitzungen();
+    fn show_author_results(&mut self, sitzung: Uuid, agenda: Result<Agenda, AppError>) {
+        let Some(AuthorSearch::Searching(author)) = &self.author_search else {
+            return;
+        };
+        if sitzung != self.sitzung.id {
+            return;
+        }
+        let author = author.clone();
+        match agenda {
+            Ok(agenda) => {
+                let partial = agenda.partial("Searched");
+                let found = anträge_by_author(agenda.tops, &author);
+                if found.is_empty() {
+                    self.author_search = None;
+                    let mut status = format!("No Anträge by \"{}\" in this Sitzung", author);
+                    if let Some(partial) = &partial {
+                        status = format!("{}. {}", status, partial);
+                    }
+                    self.set_status(status);
+                } else {
+                    let results = StatefulList::with_items(found);
+                    self.author_search = Some(AuthorSearch::Results(author, results));
+                    if let Some(partial) = partial {
+                        self.set_status(partial);
+                    }
+                }
+            }
+            Err(err) => {
+                self.author_search = None;
+                self.show_error("Loading", &err, false);
+            }
+        }
     }
 
-    fn open_top(&mut self) {
-        let selected = self.tops_selected_sitzung.state.selected().unwrap();
-        let top = self.tops_selected_sitzung.items[selected].clone();
-        let url = format!("{}api/topmanager/tops/{}/anträge/", URL, top.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(url).send().unwrap();
-        let antrag: Vec<Antrag> = response.json().unwrap();
-        self.top = top;
-        //open new view with top
-        self.anträge_selected_top = StatefulList::with_items(antrag);
-        self.layout = SelectedLayout::Anträge;
+    fn handle_author_results_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let results = match &mut self.author_search {
+            Some(AuthorSearch::Results(_, results)) => results,
+            _ => {
+                if let Char('q') | Esc = key.code {
+                    self.author_search = None;
+                }
+                return;
+            }
+        };
+        match key.code {
+            Char('q') | Esc => self.author_search = None,
+            Char('j') | Down => results.next(),
+            Char('k') | Up => results.previous(),
+            Enter => {
+                let Some((top, antrag)) = results.selected_item().cloned() else {
+                    return;
+                };
+                self.author_search = None;
+                self.jump_to(session::Visit {
+                    sitzung_id: self.sitzung.id,
+                    sitzung: self.sitzung.name.clone(),
+                    top: Some(session::VisitedTop {
+                        id: top.id,
+                        name: top.name,
+                    }),
+                });
+                self.select_created = Some(antrag.id);
+            }
+            _ => {}
+        }
     }
 
-    fn create_top(&mut self) {
-        self.edit_buffer.items.push(Param {
-            titel: "Titel".to_string(),
-            text: "".to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Inhalt".to_string(),
-            text: "".to_string(),
-        });
-        self.currently_creating = Some(SelectedLayout::Tops);
+    fn open_dashboard(&mut self) {
+        self.dashboard = Some(Dashboard::default());
+        self.load_stats();
     }
 
-    fn delete_top(&mut self) {
-        let token = self.token.clone();
-        let cookie = format!("access_token={}", token);
-        let selected = self.tops_selected_sitzung.state.selected().unwrap();
-        let top = self.tops_selected_sitzung.items[selected].clone();
-        let url = format!("{}api/topmanager/top/", URL);
-        let reqwest = reqwest::blocking::Client::new();
-        let json = serde_json::json!({ "id": top.id });
-        let response = reqwest
-            .delete(url)
-            .header("Cookie", cookie)
-            .json(&json)
-            .send()
-            .unwrap();
-        self.tops_selected_sitzung = StatefulList::with_items(get_tops(self.sitzung.clone()));
+    /// Counts the Tops and Anträge of the upcoming Sitzungen.
+    fn load_stats(&mut self) {
+        let now = Local::now().naive_local();
+        let upcoming = upcoming_sitzungen(&self.all_sitzungen, now)
+            .iter()
+            .map(|s| s.id)
+            .collect();
+        if let Some(dashboard) = &mut self.dashboard {
+            dashboard.loading = true;
+            self.backend.send(Request::Stats(upcoming));
+        }
     }
 
-    fn edit_antag(&mut self) {
-        let selected = self.anträge_selected_top.state.selected().unwrap();
-        let antrag = self.anträge_selected_top.items[selected].clone();
-        let url = format!("{}api/topmanager/antrag/{}/", URL, antrag.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(url).send().unwrap();
-        let antrag: Antrag = response.json().unwrap();
-        self.antrag = antrag.clone();
-        self.edit_buffer.items.push(Param {
-            titel: "Titel".to_string(),
-            text: antrag.titel,
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Begründung".to_string(),
-            text: antrag.begründung,
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Antragstext".to_string(),
-            text: antrag.antragstext,
-        });
+    fn handle_dashboard_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('D') => self.dashboard = None,
+            KeyCode::Char('r') => {
+                // The stats are counted again once the Sitzungen arrive.
+                self.dashboard = Some(Dashboard::default());
+                self.load_sitzungen();
+            }
+            _ => {}
+        }
+    }
 
-        self.currently_editing = Some(SelectedLayout::Anträge);
+    fn load_sitzungen(&mut self) {
+        self.awaiting_sitzungen = true;
+        self.send_awaited(Request::Sitzungen);
     }
 
-    fn edit_sitzung(&mut self) {
-        let selected = self.sitzungen.state.selected().unwrap();
-        let sitzung = self.sitzungen.items[selected].clone();
-        let url = format!("{}api/topmanager/sitzung/{}/", URL, sitzung.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(url).send().unwrap();
-        let sitzung: Sitzung = response.json().unwrap();
-        self.edit_buffer.items.push(Param {
-            titel: "Datum".to_string(),
-            text: sitzung.datum.to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Name".to_string(),
-            text: sitzung.name,
-        });
-        self.currently_editing = Some(SelectedLayout::Sitzungen);
+    fn load_tops(&mut self, select: usize) {
+        self.awaiting_tops = Some((self.sitzung.id, select));
+        self.send_awaited(Request::Tops(self.sitzung.id));
     }
 
-    fn edit_top(&mut self) {
-        let selected = self.tops_selected_sitzung.state.selected().unwrap();
-        let top = self.tops_selected_sitzung.items[selected].clone();
-        let url = format!("{}api/topmanager/tops/{}/", URL, top.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.get(url).send().unwrap();
-        let top: Top = response.json().unwrap();
-        self.edit_buffer.items.push(Param {
-            titel: "Titel".to_string(),
-            text: top.name,
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Inhalt".to_string(),
-            text: top.inhalt.to_string(),
-        });
-        self.currently_editing = Some(SelectedLayout::Tops);
+    fn load_anträge(&mut self, select: usize) {
+        self.awaiting_anträge = Some((self.top.id, select));
+        self.send_awaited(Request::Anträge(self.top.id));
     }
 
-    fn create_antrag(&mut self) {
-        self.edit_buffer.items.push(Param {
-            titel: "Titel".to_string(),
-            text: "".to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Begründung".to_string(),
-            text: "".to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Antragstext".to_string(),
-            text: "".to_string(),
-        });
-        self.edit_buffer.items.push(Param {
-            titel: "Antragssteller".to_string(),
-            text: "".to_string(),
-        });
-        self.currently_creating = Some(SelectedLayout::Anträge);
+    /// Sends a request the user waits on, so Esc can cancel it.
+    fn send_awaited(&mut self, request: Request) {
+        let cancel = self.backend.send_cancellable(request);
+        let (_, cancels) = self.loading.get_or_insert_with(|| (Instant::now(), vec![]));
+        cancels.push(cancel);
     }
 
-    fn delete_antrag(&mut self) {
-        let token = self.token.clone();
-        let cookie = format!("access_token={}", token);
-        let selected = self.anträge_selected_top.state.selected().unwrap();
-        let antrag = self.anträge_selected_top.items[selected].clone();
-        let url = format!("{}api/topmanager/antrag/{}/", URL, antrag.id);
-        let reqwest = reqwest::blocking::Client::new();
-        let response = reqwest.delete(url).header("Cookie", cookie).send().unwrap();
-        self.anträge_selected_top = StatefulList::with_items(get_anträge(self.top.clone()));
+    /// Drops the lists being loaded and goes back to the view before.
+    fn cancel_loading(&mut self) {
+        if let Some((_, cancels)) = self.loading.take() {
+            cancels.into_iter().for_each(backend::Cancel::cancel);
+        }
+        match self.layout {
+            SelectedLayout::Anträge if self.awaiting_anträge.is_some() => {
+                self.switch_layout(SelectedLayout::Tops)
+            }
+            SelectedLayout::Tops if self.awaiting_tops.is_some() => {
+                self.switch_layout(SelectedLayout::Sitzungen)
+            }
+            _ => {}
+        }
+        self.awaiting_tops = None;
+        self.awaiting_anträge = None;
+        self.awaiting_sitzungen = false;
+        self.set_status("Loading cancelled");
     }
 
-    fn edit_value(&mut self) {
-        let selected = self.edit_buffer.state.selected().unwrap();
-        let param = self.edit_buffer.items[selected].clone();
-        self.edit_param_pop = Some(param);
-        let param = self.edit_param_pop.as_ref().unwrap();
-        let text = &param.text;
-        self.current_text_area = TextArea::default();
-        self.current_text_area.insert_str(text);
+    fn reload(&mut self, reload: Reload) {
+        if let Reload::Sitzungen | Reload::All = reload {
+            self.load_sitzungen();
+        }
+        if let Reload::Tops | Reload::All = reload {
+            if !self.sitzung.id.is_nil() {
+                let selected = self.tops_selected_sitzung.state.selected().unwrap_or(0);
+                self.load_tops(selected);
+            }
+        }
+        if let Reload::Anträge | Reload::All = reload {
+            if !self.top.id.is_nil() {
+                let selected = self.anträge_selected_top.state.selected().unwrap_or(0);
+                self.load_anträge(selected);
+            }
+        }
     }
 
-    fn exit_app(&self) {
-        std::process::exit(0);
+    fn send_mutation(
+        &mut self,
+        method: Method,
+        url: String,
+        body: Option<serde_json::Value>,
+        reload: Reload,
+    ) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        self.log(format!(
+            "{} {}",
+            method,
+            url.trim_start_matches(&base_url())
+        ));
+        self.backend.send(Request::Send(Mutation {
+            method,
+            url,
+            body,
+            token,
+            reload,
+        }));
     }
 
-    fn patch(&mut self) {
-        let token = self.token.clone();
-        let cookie = format!("access_token={}", token);
-
-        if let Some(SelectedLayout::Sitzungen) = self.currently_editing {
-            let sitzung = &self.sitzung;
-            let url = format!("{}api/topmanager/sitzung/", URL);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            data["id"] = serde_json::Value::String(sitzung.id.to_string());
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
-            }
-            let response = reqwest
-                .patch(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
-        } else if let Some(SelectedLayout::Tops) = self.currently_editing {
-            let sitzung = &self.sitzung;
-            let selected = self.tops_selected_sitzung.state.selected().unwrap();
-            let top = self.tops_selected_sitzung.items[selected].clone();
-            let url = format!("{}api/topmanager/top/", URL);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            data["id"] = serde_json::Value::String(top.id.to_string());
-            data["sitzung_id"] = serde_json::Value::String(sitzung.id.to_string());
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
-            }
-            let response = reqwest
-                .patch(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
-        } else if let Some(SelectedLayout::Anträge) = self.currently_editing {
-            let antrag = &self.antrag;
-            let url = format!("{}api/topmanager/antrag/", URL);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            data["id"] = serde_json::Value::String(antrag.id.to_string());
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
+    fn receive_responses(&mut self) {
+        while let Some(response) = self.backend.try_recv() {
+            self.handle_response(response);
+            self.select_created_item();
+        }
+        if self.awaiting_tops.is_none()
+            && self.awaiting_anträge.is_none()
+            && !self.awaiting_sitzungen
+        {
+            self.loading = None;
+        }
+    }
+
+    fn select_created_item(&mut self) {
+        let Some(id) = self.select_created else {
+            return;
+        };
+        let mut index = self.created_index(id);
+        if index.is_none() && self.reveal_created(id) {
+            index = self.created_index(id);
+        }
+        if let Some(index) = index {
+            self.select_created = None;
+            match self.layout {
+                SelectedLayout::Sitzungen => self.sitzungen.state.select(Some(index)),
+                SelectedLayout::Tops => self.tops_selected_sitzung.state.select(Some(index)),
+                SelectedLayout::Anträge => {
+                    self.anträge_selected_top.state.select(Some(index));
+                    self.detail_scroll = 0;
+                }
+            }
+            if std::mem::take(&mut self.edit_created) && self.layout == SelectedLayout::Anträge {
+                self.edit_antag();
             }
-            let response = reqwest
-                .patch(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
         }
     }
 
-    fn put(&mut self) {
-        let token = self.token.clone();
-        let cookie = format!("access_token={}", token);
-
-        if let Some(SelectedLayout::Sitzungen) = self.currently_creating {
-            let url = format!("{}api/topmanager/sitzung/", URL);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
-            }
-            let response = reqwest
-                .put(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
-        } else if let Some(SelectedLayout::Tops) = self.currently_creating {
-            let url = format!("{}api/topmanager/sitzung/{}/top/", URL, self.sitzung.id);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
-            }
-            let response = reqwest
-                .put(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
-        } else if let Some(SelectedLayout::Anträge) = self.currently_creating {
-            let url = format!("{}api/topmanager/top/{}/antrag/", URL, self.top.id);
-            let reqwest = reqwest::blocking::Client::new();
-            let mut data = serde_json::json!({});
-            for param in &self.edit_buffer.items {
-                data[param.titel.clone().to_lowercase()] =
-                    serde_json::Value::String((param.text).to_string());
-            }
-
-            let response = reqwest
-                .put(url)
-                .header("Cookie", cookie)
-                .json(&data)
-                .send()
-                .unwrap();
+    fn created_index(&self, id: Uuid) -> Option<usize> {
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.items.iter().position(|s| s.id == id),
+            SelectedLayout::Tops => self
+                .tops_selected_sitzung
+                .items
+                .iter()
+                .position(|t| t.id == id),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .items
+                .iter()
+                .position(|a| a.id == id),
         }
     }
 
-    fn update(&mut self) {
-        let value = self.current_text_area.lines().concat();
-        self.edit_buffer.items[self.edit_buffer.state.selected().unwrap()].text = value;
+    /// Clears the filter or shows past Sitzungen if either hides the loaded
+    /// item `id`. Returns whether anything changed.
+    fn reveal_created(&mut self, id: Uuid) -> bool {
+        let filtered = match self.filter.as_ref().map(|filter| &filter.unfiltered) {
+            Some(Unfiltered::Sitzungen) => self.all_sitzungen.iter().any(|s| s.id == id),
+            Some(Unfiltered::Tops(all)) => all.iter().any(|t| t.id == id),
+            Some(Unfiltered::Anträge(all)) => all.iter().any(|a| a.id == id),
+            None => false,
+        };
+        let filtered = filtered
+            && self
+                .filter
+                .as_ref()
+                .is_some_and(|filter| filter.layout() == self.layout);
+        if filtered {
+            self.clear_filter();
+        }
+        let past = self.layout == SelectedLayout::Sitzungen
+            && !self.show_past
+            && self.all_sitzungen.iter().any(|s| s.id == id);
+        if past {
+            self.show_past = true;
+            self.refresh_visible_sitzungen();
+        }
+        filtered || past
     }
-}
 
-impl App<'_> {
-    fn run(&mut self, mut terminal: Terminal<impl Backend>) -> io::Result<()> {
-        loop {
-            self.draw(&mut terminal)?;
-            if self.edit_param_pop.is_some() {
-                self.handle_text_area()?;
-            } else {
-                if self.currently_editing.is_some() {
-                    if let Some(SelectedLayout::Sitzungen) = self.currently_editing {
-                        //edit sitzung
-                        self.handle_edit(&mut terminal)?;
-                    } else if let Some(SelectedLayout::Tops) = self.currently_editing {
-                        //edit top
-                        self.handle_edit(&mut terminal)?;
-                    } else if let Some(SelectedLayout::Anträge) = self.currently_editing {
-                        //edit antrag
-                        self.handle_edit(&mut terminal)?;
+    fn handle_response(&mut self, response: Response) {
+        let editing = self.currently_editing.is_some() || self.currently_creating.is_some();
+        match response {
+            Response::Sitzungen(Ok(sitzungen)) => {
+                self.awaiting_sitzungen = false;
+                self.set_sitzungen(sitzungen);
+            }
+            Response::Tops(id, result) => {
+                let prefetched = self.prefetching.remove(&id);
+                let awaited = match self.awaiting_tops {
+                    Some((awaited, index)) if awaited == id => {
+                        self.awaiting_tops = None;
+                        Some(index)
                     }
-                } else if self.currently_creating.is_some() {
-                    if let Some(SelectedLayout::Sitzungen) = self.currently_creating {
-                        //edit sitzung
-                        self.handle_edit(&mut terminal)?;
-                    } else if let Some(SelectedLayout::Tops) = self.currently_creating {
-                        //edit top
-                        self.handle_edit(&mut terminal)?;
-                    } else if let Some(SelectedLayout::Anträge) = self.currently_creating {
-                        //edit antrag
-                        self.handle_edit(&mut terminal)?;
+                    _ => None,
+                };
+                match (result, awaited) {
+                    (Ok(tops), Some(index)) => {
+                        self.set_tops(tops);
+                        self.tops_selected_sitzung.select_nearest(index);
                     }
-                } else if let SelectedLayout::Sitzungen = self.layout {
-                    self.handle_sitzungen(&mut terminal)?;
-                } else if let SelectedLayout::Tops = self.layout {
-                    self.handle_tops(&mut terminal)?;
-                } else {
-                    self.handle_anträge(&mut terminal)?;
+                    (Ok(tops), None) if prefetched => {
+                        self.tops_cache.insert(id, tops);
+                    }
+                    (Err(err), Some(_)) => self.show_error("Loading", &err, false),
+                    _ => {}
                 }
             }
-        }
-    }
-
-    fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        terminal.draw(|f| f.render_widget(self, f.size()))?;
-        Ok(())
-    }
-
-    fn handle_edit(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                use KeyCode::*;
-                match key.code {
-                    Char('q') | Esc => self.exit_edit(),
-                    Char('h') | Left => self.edit_buffer.unselect(),
-                    Char('j') | Down => self.edit_buffer.next(),
-                    Char('k') | Up => self.edit_buffer.previous(),
-                    Char('e') => self.edit_value(),
-                    _ => {}
+            Response::Anträge(id, result) => match (result, self.awaiting_anträge) {
+                (Ok(anträge), Some((awaited, index))) if awaited == id => {
+                    self.awaiting_anträge = None;
+                    self.set_anträge(anträge);
+                    self.anträge_selected_top.select_nearest(index);
+                }
+                (Err(err), Some((awaited, _))) if awaited == id => {
+                    self.awaiting_anträge = None;
+                    self.show_error("Loading", &err, false);
                 }
+                _ => {}
+            },
+            Response::AntragCount(id, count) => {
+                self.counting.remove(&id);
+                self.antrag_counts.insert(id, count);
+                self.hide_resolved_tops();
             }
-        }
-        Ok(())
-    }
-
-    fn handle_text_area(&mut self) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Esc => {
-                        self.update();
-                        self.edit_param_pop = None;
+            Response::Sitzung(Ok(sitzung)) if self.locating == Some(sitzung.id) => {
+                self.locating = None;
+                self.sitzung = sitzung;
+                self.show_breadcrumb();
+            }
+            Response::Top(Ok(top)) if self.locating == Some(top.id) => self.located_top(top),
+            Response::Sitzung(Ok(sitzung)) if !editing => self.open_sitzung_editor(sitzung),
+            Response::Top(Ok(top)) if !editing => self.open_top_editor(top),
+            Response::Antrag(Ok(antrag)) if !editing => self.open_antrag_editor(antrag),
+            Response::Sitzung(Ok(sitzung)) => {
+                self.update_edit_version(sitzung.id, sitzung.updated_at)
+            }
+            Response::Top(Ok(top)) => self.update_edit_version(top.id, top.updated_at),
+            Response::Antrag(Ok(antrag)) => self.update_edit_version(antrag.id, antrag.updated_at),
+            Response::NotesSaved(result) => match (&mut self.notes, result) {
+                (Some(notes), Ok(())) => notes.saving = false,
+                (Some(notes), Err(err)) => {
+                    notes.saving = false;
+                    notes.error = Some(err.to_string());
+                }
+                (None, Ok(())) => self.finish_quit(true),
+                (None, Err(err)) => {
+                    self.show_error("Saving the notes", &err, false);
+                    self.finish_quit(false);
+                }
+            },
+            Response::Created(mutation, result) => {
+                let reload = mutation.reload;
+                match result {
+                    Ok(id) => {
+                        self.close_after_save();
+                        // Once the user moved on, selecting it would jump a
+                        // later reload of another list.
+                        let shown = matches!(
+                            (reload, &self.layout),
+                            (Reload::All, _)
+                                | (Reload::Sitzungen, SelectedLayout::Sitzungen)
+                                | (Reload::Tops, SelectedLayout::Tops)
+                                | (Reload::Anträge, SelectedLayout::Anträge)
+                        );
+                        if shown {
+                            self.select_created = Some(id);
+                        }
+                        if self.layout == SelectedLayout::Tops {
+                            // A new Top has no open Anträge yet and would vanish.
+                            self.open_tops_only = false;
+                        }
+                        self.last_failed = None;
+                        self.finish_quit(true);
                     }
-                    _ => {
-                        self.current_text_area.input(key);
+                    Err(err) => {
+                        self.select_created = None;
+                        self.edit_created = false;
+                        self.leave_after_save = false;
+                        self.show_error("Creating", &err, true);
+                        self.last_failed = Some(Request::Create(mutation));
+                        self.finish_quit(false);
+                    }
+                }
+                self.reload(reload);
+            }
+            Response::Sent(mutation, result) => {
+                let reload = mutation.reload;
+                let saving = self.saving.take_if(|saving| saving.is(&mutation));
+                match (result, saving) {
+                    (Ok(()), saving) => {
+                        self.log(format!("{} done", mutation.method));
+                        if let Some(saving) = saving {
+                            // Only now the sent params are what the backend has.
+                            self.edit_original = saving.params;
+                            self.set_status("Saved");
+                            self.refetch_edited();
+                            self.close_after_save();
+                            self.finish_quit(true);
+                        }
+                        self.last_failed = None;
+                    }
+                    (Err(AppError::Conflict), saving) => {
+                        self.conflict = true;
+                        if saving.is_some() {
+                            self.leave_after_save = false;
+                            self.finish_quit(false);
+                        }
+                    }
+                    (Err(err), saving) => {
+                        let what = if saving.is_some() {
+                            "Saving"
+                        } else {
+                            "Request"
+                        };
+                        self.show_error(what, &err, true);
+                        self.last_failed = Some(Request::Send(mutation));
+                        if saving.is_some() {
+                            self.leave_after_save = false;
+                            self.finish_quit(false);
+                        }
+                    }
+                }
+                self.reload(reload);
+            }
+            Response::Trash(url, result) => match (&mut self.trash, result) {
+                (Some(trash), Ok(items)) if trash.url == url => {
+                    trash.items = StatefulList::with_items(items);
+                    trash.loading = false;
+                }
+                (Some(trash), Err(err)) if trash.url == url => {
+                    trash.loading = false;
+                    self.show_error("Loading the trash", &err, false);
+                }
+                _ => {}
+            },
+            Response::Malformed { url, count } => {
+                self.set_status(format!("{} entries from {} could not be read", count, url));
+            }
+            Response::ImportProgress { done, total, titel } => {
+                if let Some(ImportStep::Running {
+                    done: shown_done,
+                    total: shown_total,
+                    titel: shown_titel,
+                    ..
+                }) = &mut self.import
+                {
+                    *shown_done = done;
+                    *shown_total = total;
+                    *shown_titel = titel;
+                }
+            }
+            Response::Imported(results) => self.show_import_report(results),
+            Response::MergeProgress { done, total, titel } => {
+                if let Some(MergeStep::Running {
+                    done: shown_done,
+                    total: shown_total,
+                    titel: shown_titel,
+                    ..
+                }) = &mut self.merge
+                {
+                    *shown_done = done;
+                    *shown_total = total;
+                    *shown_titel = titel;
+                }
+            }
+            Response::Merged(Ok(report)) => self.show_merge_report(report),
+            Response::Merged(Err(err)) => {
+                self.merge = None;
+                self.show_error("Merging", &err, false);
+                self.reload(Reload::All);
+            }
+            Response::Agenda(id, agenda) => {
+                if let Ok(agenda) = &agenda {
+                    self.log_skipped(&agenda.failed);
+                }
+                let protocol = self
+                    .protocol
+                    .as_mut()
+                    .filter(|protocol| protocol.sitzung == id && protocol.agenda.is_none());
+                match protocol {
+                    Some(protocol) => {
+                        protocol.agenda = Some(agenda.map_err(|err| err.to_string()));
                     }
+                    None => self.show_author_results(id, agenda),
+                }
+            }
+            Response::Raw(url, result) => {
+                if let Some(raw) = self.raw_json.as_mut().filter(|raw| raw.url == url) {
+                    raw.text = Some(match result {
+                        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or_default(),
+                        Err(err) => format!("{} Loading failed: {}", err.icon(), err),
+                    });
+                }
+            }
+            Response::Version(version) => {
+                self.version_warning = version.and_then(|version| version_warning(&version));
+            }
+            Response::Stats(stats) => {
+                if let Ok(stats) = &stats {
+                    self.log_skipped(&stats.failed);
+                }
+                if let Some(dashboard) = &mut self.dashboard {
+                    dashboard.loading = false;
+                    dashboard.stats = Some(stats.map_err(|err| err.to_string()));
+                }
+            }
+            Response::RenumberProgress { done, total } => {
+                self.set_status(format!("Renumbering Tops… {}/{}", done, total))
+            }
+            Response::Renumbered(results) => self.show_renumbered(results),
+            Response::TopCreated { top, anträge } => {
+                if top.is_ok() {
+                    self.close_after_save();
+                } else {
+                    self.leave_after_save = false;
                 }
+                self.finish_quit(top.is_ok());
+                self.show_top_created(top, anträge);
+            }
+            Response::Login(Ok(token)) => {
+                self.user = keycloak::user(&token.secret);
+                self.token = Some(token.secret);
+                self.token_expires = token.expires_at;
+                self.set_status("Re-authenticated");
+            }
+            Response::Login(Err(err)) => {
+                self.set_status(format!("Re-authentication failed: {:#}", err))
+            }
+            Response::Sitzungen(Err(err)) => {
+                self.awaiting_sitzungen = false;
+                self.show_error("Loading", &err, false)
+            }
+            Response::Sitzung(Err(err)) | Response::Top(Err(err)) | Response::Antrag(Err(err)) => {
+                self.locating = None;
+                self.show_error("Loading", &err, false)
             }
         }
-        Ok(())
     }
 
-    fn handle_sitzungen(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                use KeyCode::*;
-                match key.code {
-                    Char('q') | Esc => self.exit_app(),
-                    Char('h') | Left => self.sitzungen.unselect(),
-                    Char('j') | Down => self.sitzungen.next(),
-                    Char('k') | Up => self.sitzungen.previous(),
-                    Char('o') => self.open_sitzung(),
-                    Char('e') => self.edit_sitzung(),
-                    Char('p') => self.create_sitzung(),
-                    Char('d') => self.delete_sitzung(),
-                    _ => {}
-                }
+    fn visible_sitzungen(&self) -> Vec<Sitzung> {
+        let now = Local::now().naive_local();
+        let mut sitzungen: Vec<Sitzung> = self
+            .all_sitzungen
+            .iter()
+            .filter(|s| self.show_past || s.datum >= now || self.session.pinned.contains(&s.id))
+            .cloned()
+            .collect();
+        sitzungen.sort_by(compare_sitzungen(
+            self.sitzungen_sort,
+            now,
+            &self.session.pinned,
+        ));
+        match &self.filter {
+            Some(filter) if filter.layout() == SelectedLayout::Sitzungen => {
+                fuzzy_rank(sitzungen, &filter.query(), |s| &s.name)
             }
+            _ => sitzungen,
         }
-        Ok(())
     }
 
-    fn handle_tops(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                use KeyCode::*;
-                match key.code {
-                    Char('q') | Esc => self.switch_layout(SelectedLayout::Sitzungen),
-                    Char('h') | Left => self.tops_selected_sitzung.unselect(),
-                    Char('j') | Down => self.tops_selected_sitzung.next(),
-                    Char('k') | Up => self.tops_selected_sitzung.previous(),
-                    Char('o') => self.open_top(),
-                    Char('e') => self.edit_top(),
-                    Char('p') => self.create_top(),
-                    Char('d') => self.delete_top(),
-                    _ => {}
-                }
-            }
+    fn toggle_past_sitzungen(&mut self) {
+        self.show_past = !self.show_past;
+        self.refresh_visible_sitzungen();
+    }
+
+    fn toggle_pin(&mut self) {
+        let Some(id) = self.sitzungen.selected_item().map(|s| s.id) else {
+            return;
+        };
+        if !self.session.pinned.remove(&id) {
+            self.session.pinned.insert(id);
         }
-        Ok(())
+        if let Err(err) = self.session.save() {
+            self.set_status(format!("Could not save pins: {:#}", err));
+        }
+        self.refresh_visible_sitzungen();
     }
 
-    fn handle_anträge(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
-                use KeyCode::*;
-                match key.code {
-                    Char('q') | Esc => self.switch_layout(SelectedLayout::Tops),
-                    Char('h') | Left => self.anträge_selected_top.unselect(),
-                    Char('j') | Down => self.anträge_selected_top.next(),
-                    Char('k') | Up => self.anträge_selected_top.previous(),
-                    Char('e') => self.edit_antag(),
-                    Char('p') => self.create_antrag(),
-                    Char('d') => self.delete_antrag(),
-                    _ => {}
-                }
-            }
+    fn refresh_visible_sitzungen(&mut self) {
+        let selected = self.sitzungen.selected_item().map(|s| s.id);
+        let visible = self.visible_sitzungen();
+        self.sitzungen.set_items(visible);
+        if let Some(index) =
+            selected.and_then(|id| self.sitzungen.items.iter().position(|s| s.id == id))
+        {
+            self.sitzungen.state.select(Some(index));
         }
-        Ok(())
+        self.schedule_prefetch();
     }
 
-    fn switch_layout(&mut self, layout: SelectedLayout) {
-        self.layout = layout.clone();
+    fn set_tops(&mut self, mut tops: Vec<Top>) {
+        tops.sort_by(compare_tops(self.tops_sort));
+        if let Some(filter) = &mut self.filter {
+            if let Unfiltered::Tops(all) = &mut filter.unfiltered {
+                *all = tops.clone();
+                tops = fuzzy_rank(tops, &filter.query(), |t| &t.name);
+            }
+        }
+        self.tops_selected_sitzung = StatefulList::with_items(tops);
+        self.hide_resolved_tops();
     }
 
-    fn exit_edit(&mut self) {
-        if let Some(editing) = &self.currently_editing {
-            self.patch();
-            self.currently_editing = None;
+    /// Drops Tops known to have no open Anträge from the list while
+    /// `open_tops_only` is on. Tops not counted yet stay until they are.
+    fn hide_resolved_tops(&mut self) {
+        if !self.open_tops_only {
+            return;
         }
-        if let Some(creating) = &self.currently_creating {
-            self.put();
-            self.currently_creating = None;
+        let counts = &self.antrag_counts;
+        let list = &mut self.tops_selected_sitzung;
+        let selected = list.selected_item().map(|t| t.id);
+        let tops = std::mem::take(&mut list.items)
+            .into_iter()
+            .filter(|top| !matches!(counts.get(&top.id), Some(Some(count)) if count.open == 0))
+            .collect();
+        list.set_items(tops);
+        if let Some(index) = list.items.iter().position(|t| Some(t.id) == selected) {
+            list.state.select(Some(index));
         }
-        self.sitzungen = StatefulList::with_items(get_sitzungen());
-        self.tops_selected_sitzung = StatefulList::with_items(get_tops(self.sitzung.clone()));
-        self.anträge_selected_top = StatefulList::with_items(get_anträge(self.top.clone()));
-        self.edit_buffer = StatefulList::with_items(vec![]);
     }
-}
-
-impl Widget for &mut App<'_> {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let vertical = Layout::vertical([
-            Constraint::Length(2),
-            Constraint::Min(0),
-            Constraint::Length(2),
-        ]);
-        let [header_area, rest_area, footer_area] = vertical.areas(area);
 
-        render_title(header_area, buf);
-        if let Some(editing) = &self.edit_param_pop {
-            self.render_edit_param(rest_area, buf);
-        } else if let Some(editing) = &self.currently_editing {
-            self.render_edit(rest_area, buf);
-        } else if let Some(creating) = &self.currently_creating {
-            self.render_edit(rest_area, buf);
+    fn toggle_open_tops_only(&mut self) {
+        self.open_tops_only = !self.open_tops_only;
+        if self.open_tops_only {
+            self.hide_resolved_tops();
+            self.set_status("Only Tops with open Anträge");
         } else {
-            self.render_overview(rest_area, buf);
+            // The hidden Tops are gone from the list, fetch them again.
+            self.select_created = self.tops_selected_sitzung.selected_item().map(|t| t.id);
+            self.load_tops(0);
+            self.set_status("All Tops");
         }
-        render_footer(footer_area, buf);
     }
-}
 
-impl App<'_> {
-    fn render_overview(&mut self, area: Rect, buf: &mut Buffer) {
-        let title = match self.layout {
-            SelectedLayout::Sitzungen => "Sitzungen",
-            SelectedLayout::Tops => "Tops",
-            SelectedLayout::Anträge => "Anträge",
-        };
-        let outer_block = Block::default()
-            .borders(Borders::NONE)
-            .fg(TEXT_COLOR)
-            .bg(TODO_HEADER_BG)
-            .title(title)
-            .title_alignment(Alignment::Center);
-        let inner_block = Block::default()
-            .borders(Borders::NONE)
-            .fg(TEXT_COLOR)
-            .bg(NORMAL_ROW_COLOR);
+    fn sort(&self) -> Sort {
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen_sort,
+            SelectedLayout::Tops => self.tops_sort,
+            SelectedLayout::Anträge => self.anträge_sort,
+        }
+    }
 
-        let outer_area = area;
-        let inner_area = outer_block.inner(outer_area);
+    fn sort_mut(&mut self) -> &mut Sort {
+        match self.layout {
+            SelectedLayout::Sitzungen => &mut self.sitzungen_sort,
+            SelectedLayout::Tops => &mut self.tops_sort,
+            SelectedLayout::Anträge => &mut self.anträge_sort,
+        }
+    }
 
-        outer_block.render(outer_area, buf);
+    fn cycle_sort_key(&mut self) {
+        let keys = self.layout.sort_keys();
+        let sort = self.sort_mut();
+        let index = keys.iter().position(|key| *key == sort.key).unwrap_or(0);
+        sort.key = keys[(index + 1) % keys.len()];
+        self.resort();
+    }
 
-        let mut listelement: Vec<String> = vec![];
-        if let SelectedLayout::Sitzungen = self.layout {
-            listelement = self
-                .sitzungen
-                .items
-                .iter()
-                .map(|s| s.name.clone())
-                .collect();
-        } else if let SelectedLayout::Tops = self.layout {
-            listelement = self
+    fn toggle_sort_order(&mut self) {
+        let sort = self.sort_mut();
+        sort.descending = !sort.descending;
+        self.resort();
+    }
+
+    fn resort(&mut self) {
+        let sort = self.sort();
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.sort_keeping_selection(
+                |s| s.id,
+                compare_sitzungen(sort, Local::now().naive_local(), &self.session.pinned),
+            ),
+            SelectedLayout::Tops => self
                 .tops_selected_sitzung
-                .items
-                .iter()
-                .map(|t| t.name.clone())
-                .collect();
-        } else {
-            listelement = self
+                .sort_keeping_selection(|t| t.id, compare_tops(sort)),
+            SelectedLayout::Anträge => self
                 .anträge_selected_top
-                .items
-                .iter()
-                .map(|a| a.titel.clone())
-                .collect();
+                .sort_keeping_selection(|a| a.id, compare_anträge(sort)),
         }
-        let items = List::new(listelement)
-            .block(inner_block)
-            .highlight_style(
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .add_modifier(Modifier::REVERSED)
-                    .fg(SELECTED_STYLE_FG),
-            )
-            .highlight_symbol(">")
-            .highlight_spacing(HighlightSpacing::Always);
+        self.set_status(format!("Sorted by {}", sort.label()));
+    }
 
-        if let SelectedLayout::Sitzungen = self.layout {
-            StatefulWidget::render(items, inner_area, buf, &mut self.sitzungen.state);
-        } else if let SelectedLayout::Tops = self.layout {
-            StatefulWidget::render(
-                items,
-                inner_area,
-                buf,
-                &mut self.tops_selected_sitzung.state,
-            );
-        } else {
-            StatefulWidget::render(items, inner_area, buf, &mut self.anträge_selected_top.state);
+    fn open_sitzung(&mut self) {
+        let Some(sitzung) = self.sitzungen.selected_item().cloned() else {
+            return;
+        };
+        self.remember_visit(session::Visit {
+            sitzung_id: sitzung.id,
+            sitzung: sitzung.name.clone(),
+            top: None,
+        });
+        self.sitzung = sitzung;
+        match self.tops_cache.remove(&self.sitzung.id) {
+            Some(tops) => self.set_tops(tops),
+            None => {
+                self.set_tops(vec![]);
+                self.load_tops(0);
+            }
         }
+        //open new view with sitzung
+        self.switch_layout(SelectedLayout::Tops);
     }
-    fn render_edit(&mut self, area: Rect, buf: &mut Buffer) {
-        if let Some(editing) = &self.currently_editing {
-            let title = match editing {
-                SelectedLayout::Sitzungen => "Edit Sitzung",
-                SelectedLayout::Tops => "Edit Top",
-                SelectedLayout::Anträge => "Edit Antrag",
-            };
-            let outer_block = Block::default()
-                .borders(Borders::NONE)
-                .fg(TEXT_COLOR)
-                .bg(TODO_HEADER_BG)
-                .title(title)
-                .title_alignment(Alignment::Center);
-            let inner_block = Block::default()
-                .borders(Borders::NONE)
-                .fg(TEXT_COLOR)
-                .bg(NORMAL_ROW_COLOR);
 
-            let outer_area = area;
-            let inner_area = outer_block.inner(outer_area);
+    fn open_todays_sitzung(&mut self) {
+        let now = Local::now().naive_local();
+        match find_todays_sitzung(&self.sitzungen.items, now) {
+            Some((index, true)) => {
+                self.sitzungen.state.select(Some(index));
+                self.open_sitzung();
+            }
+            Some((index, false)) => {
+                self.sitzungen.state.select(Some(index));
+                self.set_status("No Sitzung today, selected the next one");
+            }
+            None => self.set_status("No Sitzung today or later"),
+        }
+    }
 
-            outer_block.render(outer_area, buf);
+    fn schedule_prefetch(&mut self) {
+        if let Some(selected) = self.sitzungen.state.selected() {
+            let id = self.sitzungen.items[selected].id;
+            self.prefetch_due = Some((id, Instant::now()));
+        }
+    }
 
-            let mut listelement: Vec<String> = vec![];
-            listelement = self
-                .edit_buffer
-                .items
-                .iter()
-                .map(|p| format!("{}: {}", p.titel, p.text))
-                .collect();
+    fn prefetch_tops(&mut self) {
+        let Some((id, since)) = self.prefetch_due else {
+            return;
+        };
+        if since.elapsed() < PREFETCH_DEBOUNCE {
+            return;
+        }
+        self.prefetch_due = None;
+        if self.tops_cache.contains_key(&id) || !self.prefetching.insert(id) {
+            return;
+        }
+        self.backend.send(Request::Tops(id));
+    }
 
-            let items = List::new(listelement)
-                .block(inner_block)
-                .highlight_style(
-                    Style::default()
-                        .add_modifier(Modifier::BOLD)
-                        .add_modifier(Modifier::REVERSED)
-                        .fg(SELECTED_STYLE_FG),
-                )
-                .highlight_symbol(">")
-                .highlight_spacing(HighlightSpacing::Always);
-            StatefulWidget::render(items, inner_area, buf, &mut self.edit_buffer.state);
+    fn handle_jump_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                let mut digits = self
+                    .pending_jump
+                    .take()
+                    .map(|(digits, _)| digits)
+                    .unwrap_or_default();
+                digits.push(c);
+                self.pending_jump = Some((digits, Instant::now()));
+                true
+            }
+            KeyCode::Enter if self.pending_jump.is_some() => {
+                self.apply_jump();
+                true
+            }
+            KeyCode::Esc if self.pending_jump.is_some() => {
+                self.pending_jump = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_jump(&mut self) {
+        let Some((digits, _)) = self.pending_jump.take() else {
+            return;
+        };
+        let Ok(number) = digits.parse::<usize>() else {
+            return;
+        };
+        let index = number.saturating_sub(1);
+        match self.layout {
+            SelectedLayout::Sitzungen => {
+                self.sitzungen.select_nearest(index);
+                self.schedule_prefetch();
+            }
+            SelectedLayout::Tops => self.tops_selected_sitzung.select_nearest(index),
+            SelectedLayout::Anträge => self.anträge_selected_top.select_nearest(index),
         }
+    }
+
+    /// Letters extend the prefix, Backspace shortens it, Enter and Esc end
+    /// it. Any other key ends it and is handled as usual, returns `false` then.
+    fn handle_type_ahead_key(&mut self, key: KeyEvent) -> bool {
+        let Some((typed, since)) = &mut self.type_ahead else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => typed.push(c),
+            KeyCode::Backspace => {
+                typed.pop();
+            }
+            KeyCode::Enter | KeyCode::Esc => {
+                self.type_ahead = None;
+                return true;
+            }
+            _ => {
+                self.type_ahead = None;
+                return false;
+            }
+        }
+        *since = Instant::now();
+        let typed = typed.clone();
+        let index = match self.layout {
+            SelectedLayout::Sitzungen => {
+                prefix_match(self.sitzungen.items.iter().map(|s| s.name.as_str()), &typed)
+            }
+            SelectedLayout::Tops => prefix_match(
+                self.tops_selected_sitzung
+                    .items
+                    .iter()
+                    .map(|t| t.name.as_str()),
+                &typed,
+            ),
+            SelectedLayout::Anträge => prefix_match(
+                self.anträge_selected_top
+                    .items
+                    .iter()
+                    .map(|a| a.titel.as_str()),
+                &typed,
+            ),
+        };
+        let Some(index) = index else {
+            return true;
+        };
+        match self.layout {
+            SelectedLayout::Sitzungen => {
+                self.sitzungen.state.select(Some(index));
+                self.schedule_prefetch();
+            }
+            SelectedLayout::Tops => self.tops_selected_sitzung.state.select(Some(index)),
+            SelectedLayout::Anträge => {
+                self.anträge_selected_top.state.select(Some(index));
+                self.detail_scroll = 0;
+            }
+        }
+        true
+    }
+
+    fn reload_config(&mut self) {
+        match config::load() {
+            Ok((config, keymap)) => {
+                self.config = config;
+                self.keymap = keymap;
+                self.set_status("Config reloaded");
+            }
+            Err(err) => self.set_status(format!("Config error: {:#}", err)),
+        }
+    }
+
+    /// Shows `message` in the status line and keeps it in the action log.
+    fn set_status(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.log(message.clone());
+        self.status = Some((message, Instant::now()));
+        self.status_color = None;
+    }
+
+    /// Shows a failed request with the icon, color and suggested action of
+    /// its kind. With `retry` a retryable error suggests `R` instead.
+    fn show_error(&mut self, what: &str, err: &AppError, retry: bool) {
+        let action = if retry && err.retryable() {
+            "R to retry"
+        } else {
+            err.action()
+        };
+        self.set_status(format!(
+            "{} {} failed: {} — {}",
+            err.icon(),
+            what,
+            err,
+            action
+        ));
+        self.status_color = Some(err.color());
+        self.last_error = Some(format!("{} failed: {:?}", what, err));
+    }
+
+    /// Tops a whole-Sitzung operation went on without.
+    fn log_skipped(&mut self, failed: &[String]) {
+        for failure in failed {
+            self.log(format!("Skipped Top {}", failure));
+        }
+    }
+
+    /// Records an action in the log without showing it in the status line.
+    fn log(&mut self, message: impl Into<String>) {
+        if self.action_log.len() == ACTION_LOG_LEN {
+            self.action_log.pop_front();
+        }
+        self.action_log.push_back((Local::now(), message.into()));
+    }
+
+    fn selected_id(&self) -> Option<Uuid> {
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.selected_item().map(|s| s.id),
+            SelectedLayout::Tops => self.tops_selected_sitzung.selected_item().map(|t| t.id),
+            SelectedLayout::Anträge => self.anträge_selected_top.selected_item().map(|a| a.id),
+        }
+    }
+
+    fn selected_debug(&self) -> Option<String> {
+        match self.layout {
+            SelectedLayout::Sitzungen => {
+                self.sitzungen.selected_item().map(|s| format!("{:#?}", s))
+            }
+            SelectedLayout::Tops => self
+                .tops_selected_sitzung
+                .selected_item()
+                .map(|t| format!("{:#?}", t)),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .selected_item()
+                .map(|a| format!("{:#?}", a)),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: String) -> Result<(), arboard::Error> {
+        if self.clipboard.is_none() {
+            self.clipboard = Some(arboard::Clipboard::new()?);
+        }
+        self.clipboard.as_mut().unwrap().set_text(text)
+    }
+
+    fn set_pending_key(&mut self, key: char) {
+        self.pending_key = Some((key, Instant::now()));
+    }
+
+    fn selected_json(&self) -> Option<String> {
+        match self.layout {
+            SelectedLayout::Sitzungen => self
+                .sitzungen
+                .selected_item()
+                .and_then(|s| serde_json::to_string_pretty(s).ok()),
+            SelectedLayout::Tops => self
+                .tops_selected_sitzung
+                .selected_item()
+                .and_then(|t| serde_json::to_string_pretty(t).ok()),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .selected_item()
+                .and_then(|a| serde_json::to_string_pretty(a).ok()),
+        }
+    }
+
+    fn yank_selected(&mut self) {
+        let Some(json) = self.selected_json() else {
+            return;
+        };
+        match self.copy_to_clipboard(json) {
+            Ok(()) => self.set_status("Copied as JSON"),
+            Err(err) => self.set_status(format!("Copy failed: {}", err)),
+        }
+    }
+
+    /// Copies the selected Top or Antrag with headings, for a wiki or chat.
+    fn copy_markdown(&mut self) {
+        let text = match self.layout {
+            SelectedLayout::Tops => self.tops_selected_sitzung.selected_item().map(export::top),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .selected_item()
+                .map(export::antrag),
+            SelectedLayout::Sitzungen => None,
+        };
+        let Some(text) = text else {
+            return;
+        };
+        match self.copy_to_clipboard(text) {
+            Ok(()) => self.set_status("Copied as Markdown"),
+            Err(err) => self.set_status(format!("Copy failed: {}", err)),
+        }
+    }
+
+    /// Copies the web address of the Sitzung's agenda, to share it.
+    fn copy_agenda_link(&mut self, id: Uuid) {
+        let template = self.config.agenda_url.as_deref();
+        let url = agenda_link(template.unwrap_or(AGENDA_URL), &base_url(), id);
+        match self.copy_to_clipboard(url.clone()) {
+            Ok(()) => self.set_status(format!("Copied {}", url)),
+            Err(err) => self.set_status(format!("{} (copy failed: {})", url, err)),
+        }
+    }
+
+    fn copy_id(&mut self) {
+        let Some(id) = self.selected_id() else {
+            return;
+        };
+        match self.copy_to_clipboard(id.to_string()) {
+            Ok(()) => self.set_status(format!("Copied id {}", id)),
+            Err(err) => self.set_status(format!("Id {} (copy failed: {})", id, err)),
+        }
+    }
+
+    fn set_anträge(&mut self, mut anträge: Vec<Antrag>) {
+        let count = backend::AntragCount::of(&anträge);
+        self.antrag_counts.insert(self.top.id, Some(count));
+        anträge.sort_by(compare_anträge(self.anträge_sort));
+        if let Some(filter) = &mut self.filter {
+            if let Unfiltered::Anträge(all) = &mut filter.unfiltered {
+                *all = anträge.clone();
+                anträge = fuzzy_rank(anträge, &filter.query(), |a| &a.titel);
+            }
+        }
+        self.anträge_selected_top = StatefulList::with_items(anträge);
+    }
+
+    fn request_antrag_counts(&mut self) {
+        let ids: Vec<Uuid> = self
+            .tops_selected_sitzung
+            .items
+            .iter()
+            .map(|t| t.id)
+            .filter(|id| !self.antrag_counts.contains_key(id) && self.counting.insert(*id))
+            .collect();
+        for id in ids {
+            self.backend.send(Request::AntragCount(id));
+        }
+    }
+
+    fn on_tick(&mut self) {
+        if let Some((_, since)) = &self.pending_key {
+            if since.elapsed() >= PENDING_KEY_TIMEOUT {
+                self.pending_key = None;
+            }
+        }
+        if let Some((_, since)) = &self.status {
+            if since.elapsed() >= STATUS_TIMEOUT {
+                self.status = None;
+            }
+        }
+        if self
+            .notes
+            .as_ref()
+            .and_then(|notes| notes.changed)
+            .is_some_and(|changed| changed.elapsed() >= NOTES_AUTOSAVE)
+        {
+            self.save_notes();
+        }
+        self.prefetch_tops();
+        if let SelectedLayout::Tops = self.layout {
+            self.request_antrag_counts();
+        }
+        if self
+            .config
+            .auto_refresh()
+            .is_some_and(|interval| self.last_auto_refresh.elapsed() >= interval)
+        {
+            self.auto_refresh();
+        }
+        if let Some((_, since)) = &self.type_ahead {
+            if since.elapsed() >= TYPE_AHEAD_TIMEOUT {
+                self.type_ahead = None;
+            }
+        }
+        if let Some((_, since)) = &self.pending_jump {
+            if since.elapsed() >= JUMP_TIMEOUT {
+                self.apply_jump();
+            }
+        }
+    }
+
+    /// Quietly reloads the current list, keeping the selected item selected.
+    /// Waits while anything is being edited or loaded.
+    fn auto_refresh(&mut self) {
+        let busy = self.currently_editing.is_some()
+            || self.currently_creating.is_some()
+            || self.edit_param_pop.is_some()
+            || self.rename.is_some()
+            || self.quick_create.is_some()
+            || self.notes.is_some()
+            || self.import.is_some()
+            || self.merge.is_some()
+            || self.loading.is_some()
+            || self.awaiting_tops.is_some()
+            || self.awaiting_anträge.is_some()
+            || self.awaiting_sitzungen;
+        if busy {
+            return;
+        }
+        self.last_auto_refresh = Instant::now();
+        if self.select_created.is_none() {
+            self.select_created = self.selected_id();
+        }
+        match self.layout {
+            SelectedLayout::Sitzungen => {
+                self.awaiting_sitzungen = true;
+                self.backend.send(Request::Sitzungen);
+            }
+            SelectedLayout::Tops if !self.sitzung.id.is_nil() => {
+                let selected = self.tops_selected_sitzung.state.selected().unwrap_or(0);
+                self.awaiting_tops = Some((self.sitzung.id, selected));
+                self.backend.send(Request::Tops(self.sitzung.id));
+            }
+            SelectedLayout::Anträge if !self.top.id.is_nil() => {
+                let selected = self.anträge_selected_top.state.selected().unwrap_or(0);
+                self.awaiting_anträge = Some((self.top.id, selected));
+                self.backend.send(Request::Anträge(self.top.id));
+            }
+            _ => {}
+        }
+    }
+
+    fn create_sitzung(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        self.edit_buffer = StatefulList::with_items(new_sitzung_params());
+        self.currently_creating = Some(SelectedLayout::Sitzungen);
+    }
+
+    fn delete_sitzung(&mut self) {
+        let Some(sitzung) = self.sitzungen.selected_item().cloned() else {
+            return;
+        };
+        let url = format!("{}api/topmanager/sitzung/", base_url());
+        let json = serde_json::json!({ "id": sitzung.id });
+        self.log(format!("Deleting Sitzung \"{}\"", sitzung.name));
+        self.send_mutation(Method::DELETE, url, Some(json), Reload::Sitzungen);
+    }
+
+    fn open_top(&mut self) {
+        let Some(top) = self.tops_selected_sitzung.selected_item().cloned() else {
+            return;
+        };
+        self.remember_visit(session::Visit {
+            sitzung_id: self.sitzung.id,
+            sitzung: self.sitzung.name.clone(),
+            top: Some(session::VisitedTop {
+                id: top.id,
+                name: top.name.clone(),
+            }),
+        });
+        self.top = top;
+        //open new view with top
+        self.set_anträge(vec![]);
+        self.load_anträge(0);
+        self.switch_layout(SelectedLayout::Anträge);
+    }
+
+    fn switch_sitzung(&mut self, forward: bool) {
+        let len = self.sitzungen.items.len();
+        if len == 0 {
+            return;
+        }
+        let current = self
+            .sitzungen
+            .items
+            .iter()
+            .position(|s| s.id == self.sitzung.id)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        let selected = self.tops_selected_sitzung.state.selected().unwrap_or(0);
+        self.sitzungen.state.select(Some(next));
+        self.sitzung = self.sitzungen.items[next].clone();
+        self.set_tops(vec![]);
+        self.load_tops(selected);
+    }
+
+    fn create_top(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        self.edit_buffer = StatefulList::with_items(new_top_params());
+        self.currently_creating = Some(SelectedLayout::Tops);
+    }
+
+    fn delete_top(&mut self) {
+        let Some(top) = self.tops_selected_sitzung.selected_item().cloned() else {
+            return;
+        };
+        let url = format!("{}api/topmanager/top/", base_url());
+        let json = serde_json::json!({ "id": top.id });
+        self.log(format!("Deleting Top \"{}\"", top.name));
+        self.send_mutation(Method::DELETE, url, Some(json), Reload::Tops);
+    }
+
+    fn edit_antag(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        self.backend.send(Request::Antrag(antrag.id));
+    }
+
+    fn open_antrag_editor(&mut self, antrag: Antrag) {
+        self.edit_buffer = StatefulList::with_items(antrag_params(&antrag));
+        self.edit_original = self.edit_buffer.items.clone();
+        self.edit_version = antrag.updated_at.clone();
+        self.edit_fetched = Some(Instant::now());
+        self.antrag = antrag;
+        self.currently_editing = Some(SelectedLayout::Anträge);
+        self.reapply_edits();
+    }
+
+    fn edit_sitzung(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(sitzung) = self.sitzungen.selected_item() else {
+            return;
+        };
+        self.backend.send(Request::Sitzung(sitzung.id));
+    }
+
+    fn open_sitzung_editor(&mut self, sitzung: Sitzung) {
+        self.edit_buffer = StatefulList::with_items(sitzung_params(&sitzung));
+        self.edit_original = self.edit_buffer.items.clone();
+        self.edit_version = sitzung.updated_at.clone();
+        self.edit_fetched = Some(Instant::now());
+        self.sitzung = sitzung;
+        self.currently_editing = Some(SelectedLayout::Sitzungen);
+        self.reapply_edits();
+    }
+
+    fn edit_top(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(top) = self.tops_selected_sitzung.selected_item() else {
+            return;
+        };
+        self.backend.send(Request::Top(top.id));
+    }
+
+    fn open_top_editor(&mut self, top: Top) {
+        self.edit_buffer = StatefulList::with_items(top_params(&top));
+        self.edit_original = self.edit_buffer.items.clone();
+        self.edit_version = top.updated_at.clone();
+        self.edit_fetched = Some(Instant::now());
+        self.currently_editing = Some(SelectedLayout::Tops);
+        self.reapply_edits();
+    }
+
+    fn switch_top(&mut self, forward: bool) {
+        let len = self.tops_selected_sitzung.items.len();
+        if len == 0 {
+            return;
+        }
+        let current = self
+            .tops_selected_sitzung
+            .items
+            .iter()
+            .position(|t| t.id == self.top.id)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+        let selected = self.anträge_selected_top.state.selected().unwrap_or(0);
+        self.tops_selected_sitzung.state.select(Some(next));
+        self.top = self.tops_selected_sitzung.items[next].clone();
+        self.set_anträge(vec![]);
+        self.load_anträge(selected);
+    }
+
+    fn create_antrag(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        self.edit_buffer = StatefulList::with_items(new_antrag_params());
+        self.apply_templates();
+        self.currently_creating = Some(SelectedLayout::Anträge);
+    }
+
+    /// The configured template for the field `key`, filled in for the
+    /// current Sitzung and Top.
+    fn template_for(&self, key: &str) -> Option<String> {
+        if key != "antragstext" && !key.ends_with(".antragstext") {
+            return None;
+        }
+        let template = self.config.antrag_template.as_deref()?;
+        if self.currently_creating == Some(SelectedLayout::Tops) {
+            // Inline Anträge belong to the Top that is being created.
+            let titel = self.edit_buffer.items.iter().find(|p| p.key == "titel");
+            let top = Top {
+                name: titel.map(|p| p.text.clone()).unwrap_or_default(),
+                ..Top::default()
+            };
+            return Some(fill_template(template, &self.sitzung, &top));
+        }
+        Some(fill_template(template, &self.sitzung, &self.top))
+    }
+
+    /// Fills empty fields that have a template with it.
+    fn apply_templates(&mut self) {
+        for index in 0..self.edit_buffer.items.len() {
+            if !self.edit_buffer.items[index].text.is_empty() {
+                continue;
+            }
+            if let Some(text) = self.template_for(&self.edit_buffer.items[index].key) {
+                self.edit_buffer.items[index].text = text;
+            }
+        }
+    }
+
+    fn reset_to_template(&mut self) {
+        let Some(param) = self.edit_buffer.selected_item() else {
+            return;
+        };
+        let selected = self.edit_buffer.state.selected().unwrap_or_default();
+        match self.template_for(&param.key) {
+            Some(text) => {
+                self.edit_buffer.items[selected].text = text;
+                self.set_status("Reset to the template");
+            }
+            None => self.set_status("No template for this field"),
+        }
+    }
+
+    /// Asks only for the title of a new Top or Antrag.
+    fn start_quick_create(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        self.quick_create = Some(TextArea::default());
+    }
+
+    fn handle_quick_create_key(&mut self, key: KeyEvent) {
+        let Some(input) = &mut self.quick_create else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.quick_create = None,
+            KeyCode::Enter => {
+                let titel = input.lines().join(" ").trim().to_string();
+                self.quick_create = None;
+                if !titel.is_empty() {
+                    self.quick_create_item(titel);
+                }
+            }
+            _ => {
+                input.input(key);
+            }
+        }
+    }
+
+    /// Fetches the selected item from its single-item endpoint and shows
+    /// the JSON as it came back, with every field.
+    fn open_raw_json(&mut self) {
+        let url = match self.layout {
+            SelectedLayout::Sitzungen => self
+                .sitzungen
+                .selected_item()
+                .map(|s| format!("{}api/topmanager/sitzung/{}/", base_url(), s.id)),
+            SelectedLayout::Tops => self
+                .tops_selected_sitzung
+                .selected_item()
+                .map(|t| format!("{}api/topmanager/tops/{}/", base_url(), t.id)),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .selected_item()
+                .map(|a| format!("{}api/topmanager/antrag/{}/", base_url(), a.id)),
+        };
+        let Some(url) = url else {
+            self.set_status("Nothing selected");
+            return;
+        };
+        self.backend.send(Request::Raw(url.clone()));
+        self.raw_json = Some(RawJson {
+            url,
+            text: None,
+            scroll: 0,
+        });
+    }
+
+    fn handle_raw_json_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(raw) = &mut self.raw_json else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc | Char('J') => self.raw_json = None,
+            Char('w') => self.toggle_wrap(),
+            Char('j') | Down => raw.scroll = raw.scroll.saturating_add(1),
+            Char('k') | Up => raw.scroll = raw.scroll.saturating_sub(1),
+            PageDown => raw.scroll = raw.scroll.saturating_add(10),
+            PageUp => raw.scroll = raw.scroll.saturating_sub(10),
+            Char('g') | Home => raw.scroll = 0,
+            Char('y') => {
+                if let Some(text) = raw.text.clone() {
+                    match self.copy_to_clipboard(text) {
+                        Ok(()) => self.set_status("Copied the JSON"),
+                        Err(err) => self.set_status(format!("Copy failed: {}", err)),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_agenda_summary(&mut self) {
+        if self.tops_selected_sitzung.items.is_empty() {
+            self.set_status("No Tops to list");
+            return;
+        }
+        self.agenda_summary = Some(agenda_line(&self.tops_selected_sitzung.items));
+    }
+
+    fn handle_agenda_summary_key(&mut self, key: KeyEvent) {
+        let Some(summary) = self.agenda_summary.take() else {
+            return;
+        };
+        if key.code == KeyCode::Char('y') {
+            match self.copy_to_clipboard(summary) {
+                Ok(()) => self.set_status("Copied the agenda"),
+                Err(err) => self.set_status(format!("Copy failed: {}", err)),
+            }
+        }
+    }
+
+    fn open_protocol(&mut self) {
+        self.backend.send(Request::Agenda(self.sitzung.id));
+        self.protocol = Some(Protocol {
+            sitzung: self.sitzung.id,
+            agenda: None,
+            scroll: 0,
+        });
+    }
+
+    fn handle_protocol_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(protocol) = &mut self.protocol else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc | Char('r') => self.protocol = None,
+            Char('w') => self.toggle_wrap(),
+            Char('j') | Down => protocol.scroll = protocol.scroll.saturating_add(1),
+            Char('k') | Up => protocol.scroll = protocol.scroll.saturating_sub(1),
+            PageDown => protocol.scroll = protocol.scroll.saturating_add(10),
+            PageUp => protocol.scroll = protocol.scroll.saturating_sub(10),
+            Char('g') | Home => protocol.scroll = 0,
+            Char('y') => {
+                if let Some(Ok(agenda)) = &protocol.agenda {
+                    let text = export::markdown(&self.sitzung, &agenda.tops);
+                    let partial = agenda.partial("Copied");
+                    match (self.copy_to_clipboard(text), partial) {
+                        (Ok(()), None) => self.set_status("Copied the protocol as Markdown"),
+                        (Ok(()), Some(partial)) => self.set_status(partial),
+                        (Err(err), _) => self.set_status(format!("Copy failed: {}", err)),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Edits the name of the selected row in place.
+    fn start_rename(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let name = match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.selected_item().map(|s| &s.name),
+            SelectedLayout::Tops => self.tops_selected_sitzung.selected_item().map(|t| &t.name),
+            SelectedLayout::Anträge => self.anträge_selected_top.selected_item().map(|a| &a.titel),
+        };
+        let Some(name) = name else {
+            return;
+        };
+        let mut input = TextArea::new(vec![name.clone()]);
+        input.set_cursor_line_style(Style::default());
+        input.move_cursor(CursorMove::End);
+        self.rename = Some(input);
+    }
+
+    fn handle_rename_key(&mut self, key: KeyEvent) {
+        let Some(input) = &mut self.rename else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.rename = None,
+            KeyCode::Enter => {
+                let name = input.lines().join(" ").trim().to_string();
+                self.rename = None;
+                if !name.is_empty() {
+                    self.rename_selected(name);
+                }
+            }
+            _ => {
+                input.input(key);
+            }
+        }
+    }
+
+    /// PATCHes only the name of the selected item, with the same id fields
+    /// the editor sends.
+    fn rename_selected(&mut self, name: String) {
+        let (mut data, version, kind, reload) = match self.layout {
+            SelectedLayout::Sitzungen => {
+                let Some(sitzung) = self.sitzungen.selected_item() else {
+                    return;
+                };
+                let data = serde_json::json!({"id": sitzung.id, "name": name});
+                (
+                    data,
+                    sitzung.updated_at.clone(),
+                    "sitzung",
+                    Reload::Sitzungen,
+                )
+            }
+            SelectedLayout::Tops => {
+                let Some(top) = self.tops_selected_sitzung.selected_item() else {
+                    return;
+                };
+                let data = serde_json::json!({
+                    "id": top.id,
+                    "sitzung_id": self.sitzung.id,
+                    "titel": name,
+                });
+                (data, top.updated_at.clone(), "top", Reload::Tops)
+            }
+            SelectedLayout::Anträge => {
+                let Some(antrag) = self.anträge_selected_top.selected_item() else {
+                    return;
+                };
+                let data = serde_json::json!({
+                    "id": antrag.id,
+                    "status": antrag.status,
+                    "titel": name,
+                });
+                (data, antrag.updated_at.clone(), "antrag", Reload::Anträge)
+            }
+        };
+        if let Some(version) = version {
+            data["updated_at"] = serde_json::Value::String(version);
+        }
+        let url = format!("{}api/topmanager/{}/", base_url(), kind);
+        self.send_mutation(Method::PATCH, url, Some(data), reload);
+        self.set_status(format!("Renaming to \"{}\"…", name));
+    }
+
+    /// Creates a Top or Antrag with `titel` and everything else left empty,
+    /// to be filled in later with `e`.
+    fn quick_create_item(&mut self, titel: String) {
+        let mut params = match self.layout {
+            SelectedLayout::Sitzungen => return,
+            SelectedLayout::Tops => new_top_params(),
+            SelectedLayout::Anträge => new_antrag_params(),
+        };
+        if let Some(param) = params.iter_mut().find(|param| param.key == "titel") {
+            param.text = titel.clone();
+        }
+        self.edit_buffer = StatefulList::with_items(params);
+        self.apply_templates();
+        self.currently_creating = Some(self.layout.clone());
+        self.put();
+        self.close_editor();
+        self.set_status(format!("Creating \"{}\"…", titel));
+    }
+
+    /// Creates a copy of the selected Antrag under the current Top and opens
+    /// it for editing once it shows up.
+    fn duplicate_antrag(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        let mut params = antrag_params(antrag);
+        let author = antrag.author.clone().unwrap_or_default();
+        params.push(Param::new("Antragssteller", "antragssteller", author));
+        let titel = format!("{} (Kopie)", antrag.titel);
+        if let Some(param) = params.iter_mut().find(|param| param.key == "titel") {
+            param.text = titel.clone();
+        }
+        self.edit_buffer = StatefulList::with_items(params);
+        self.currently_creating = Some(SelectedLayout::Anträge);
+        self.put();
+        self.close_editor();
+        self.edit_created = true;
+        self.set_status(format!("Creating \"{}\"…", titel));
+    }
+
+    fn delete_antrag(&mut self) {
+        let Some(antrag) = self.anträge_selected_top.selected_item().cloned() else {
+            return;
+        };
+        let url = format!("{}api/topmanager/antrag/{}/", base_url(), antrag.id);
+        self.log(format!("Deleting Antrag \"{}\"", antrag.titel));
+        self.send_mutation(Method::DELETE, url, None, Reload::Anträge);
+    }
+
+    fn cycle_antrag_status(&mut self) {
+        if let Some(antrag) = self.anträge_selected_top.selected_item() {
+            let status = antrag.status.next();
+            self.set_antrag_status(status);
+        }
+    }
+
+    fn set_antrag_status(&mut self, status: AntragStatus) {
+        let Some(mut antrag) = self.anträge_selected_top.selected_item().cloned() else {
+            return;
+        };
+        antrag.status = status;
+        let url = format!("{}api/topmanager/antrag/", base_url());
+        let json = serde_json::json!(antrag);
+        self.send_mutation(Method::PATCH, url, Some(json), Reload::Anträge);
+    }
+
+    /// Records a vote result on the selected Antrag and moves on to the next
+    /// one, so a whole Top can be gone through with one key per Antrag.
+    fn record_vote(&mut self, status: AntragStatus) {
+        let Some(selected) = self.anträge_selected_top.state.selected() else {
+            return;
+        };
+        self.set_antrag_status(status);
+        if let Some(antrag) = self.anträge_selected_top.items.get_mut(selected) {
+            antrag.status = status;
+        }
+        if selected + 1 < self.anträge_selected_top.items.len() {
+            self.anträge_selected_top.next();
+        }
+    }
+
+    fn select_anhang(&mut self) {
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        match antrag.anhänge.len() {
+            0 => {}
+            1 => self.open_anhang(0),
+            _ => self.anhang_picker = Some(StatefulList::with_items(antrag.anhänge.clone())),
+        }
+    }
+
+    fn handle_anhang_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(picker) = &mut self.anhang_picker else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc => self.anhang_picker = None,
+            Char('j') | Down => picker.next(),
+            Char('k') | Up => picker.previous(),
+            Enter => {
+                if let Some(index) = picker.state.selected() {
+                    self.anhang_picker = None;
+                    self.open_anhang(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn export_pdf(&mut self) {
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        let name = pdf::file_name(antrag);
+        let header = self.config.pdf_header.as_deref();
+        let written = pdf::antrag(antrag, &self.sitzung, &self.top, header)
+            .and_then(|bytes| Ok(pdf::write_new(&name, &bytes)?));
+        match written {
+            Ok(path) => self.set_status(format!("Wrote {}", path.display())),
+            Err(err) => self.set_status(format!("Could not write {}: {}", name, err)),
+        }
+    }
+
+    fn open_anhang(&mut self, index: usize) {
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        let Some(anhang) = antrag.anhänge.get(index).cloned() else {
+            return;
+        };
+        let Some(url) = web_link(&anhang) else {
+            self.set_status(format!("Not a web link, not opened: {}", anhang));
+            return;
+        };
+        if let Err(err) = open::that_detached(url.as_str()) {
+            self.set_status(format!("Could not open {}: {}", url, err));
+        }
+    }
+
+    fn open_notes(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(top) = self.tops_selected_sitzung.selected_item() else {
+            return;
+        };
+        let mut input = TextArea::from(top.protokoll.lines());
+        input.move_cursor(CursorMove::Bottom);
+        input.move_cursor(CursorMove::End);
+        self.notes = Some(Notes {
+            top: top.id,
+            input,
+            changed: None,
+            saving: false,
+            error: None,
+        });
+    }
+
+    fn handle_notes_key(&mut self, key: KeyEvent) {
+        let Some(notes) = &mut self.notes else {
+            return;
+        };
+        if key.code == KeyCode::Esc {
+            self.close_notes();
+        } else if notes.input.input(key) {
+            notes.changed = Some(Instant::now());
+        }
+    }
+
+    /// Sends the notes and keeps the local Top in sync, so closing the notes
+    /// mode does not need a reload.
+    fn save_notes(&mut self) {
+        let Some(notes) = &mut self.notes else {
+            return;
+        };
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        let protokoll = notes.input.lines().join("\n");
+        notes.changed = None;
+        notes.saving = true;
+        notes.error = None;
+        let top = notes.top;
+        if let Some(local) = self
+            .tops_selected_sitzung
+            .items
+            .iter_mut()
+            .find(|t| t.id == top)
+        {
+            local.protokoll = protokoll.clone();
+        }
+        self.backend.send(Request::SaveNotes(Mutation {
+            method: Method::PATCH,
+            url: format!("{}api/topmanager/top/", base_url()),
+            body: Some(serde_json::json!({
+                "id": top,
+                "sitzung_id": self.sitzung.id,
+                "protokoll": protokoll,
+            })),
+            token,
+            reload: Reload::Tops,
+        }));
+    }
+
+    fn close_notes(&mut self) {
+        if self
+            .notes
+            .as_ref()
+            .is_some_and(|notes| notes.changed.is_some())
+        {
+            self.save_notes();
+        }
+        self.notes = None;
+    }
+
+    /// Where the user is and what happened last, for a crash report.
+    fn crash_context(&self) -> String {
+        let mut context = format!(
+            "layout: {:?}\nbackend: {}\nsitzung: {}\ntop: {}\nselected: {}\n",
+            self.layout,
+            base_url(),
+            self.sitzung.id,
+            self.top.id,
+            self.selected_id()
+                .map_or("none".to_string(), |id| id.to_string()),
+        );
+        let editing = self
+            .currently_editing
+            .as_ref()
+            .or(self.currently_creating.as_ref());
+        if let Some(layout) = editing {
+            context.push_str(&format!("editing: {:?}\n", layout));
+        }
+        if let Some(Request::Send(mutation) | Request::Create(mutation)) = &self.last_failed {
+            context.push_str(&format!(
+                "last failed request: {} {}\n",
+                mutation.method, mutation.url
+            ));
+        }
+        if let Some(error) = &self.last_error {
+            context.push_str(&format!("last error: {}\n", error));
+        }
+        context.push_str("\nrecent actions:\n");
+        let skip = self.action_log.len().saturating_sub(20);
+        for (at, message) in self.action_log.iter().skip(skip) {
+            context.push_str(&format!("{} {}\n", at.format("%H:%M:%S"), message));
+        }
+        let token = self.token.as_deref().unwrap_or_default();
+        crash::redact(&context, &[token])
+    }
+
+    /// Sends the last failed mutation again, with the current token in case
+    /// it failed because the old one expired.
+    fn retry_failed(&mut self) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        let request = match self.last_failed.take() {
+            Some(Request::Send(mutation)) => Request::Send(Mutation { token, ..mutation }),
+            Some(Request::Create(mutation)) => Request::Create(Mutation { token, ..mutation }),
+            _ => return,
+        };
+        self.backend.send(request);
+        self.set_status("Retrying…");
+    }
+
+    fn remember_visit(&mut self, visit: session::Visit) {
+        self.session.visit(visit);
+        if let Err(err) = self.session.save() {
+            self.set_status(format!("Could not save recent items: {:#}", err));
+        }
+    }
+
+    fn open_switcher(&mut self) {
+        if self.session.recent.is_empty() {
+            self.set_status("Nothing opened yet");
+            return;
+        }
+        self.switcher = Some(StatefulList::with_items(self.session.recent.clone()));
+    }
+
+    fn handle_switcher_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(switcher) = &mut self.switcher else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc => self.switcher = None,
+            Char('j') | Down => switcher.next(),
+            Char('k') | Up => switcher.previous(),
+            Enter => {
+                if let Some(visit) = switcher.selected_item().cloned() {
+                    self.switcher = None;
+                    self.jump_to(visit);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_attendance(&mut self) {
+        let Some(sitzung) = self.sitzungen.selected_item() else {
+            return;
+        };
+        let members = attendance_roster(&self.config.members, &sitzung.anwesend);
+        if members.is_empty() {
+            self.set_status("No members configured, add a members list to the config");
+            return;
+        }
+        self.attendance = Some(Attendance {
+            sitzung: sitzung.id,
+            version: sitzung.updated_at.clone(),
+            members: StatefulList::with_items(members),
+        });
+    }
+
+    fn handle_attendance_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(attendance) = &mut self.attendance else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc => self.attendance = None,
+            Char('j') | Down => attendance.members.next(),
+            Char('k') | Up => attendance.members.previous(),
+            Char(' ') => {
+                if let Some(index) = attendance.members.state.selected() {
+                    let present = &mut attendance.members.items[index].1;
+                    *present = !*present;
+                }
+            }
+            Enter => self.save_attendance(),
+            _ => {}
+        }
+    }
+
+    fn save_attendance(&mut self) {
+        if self.read_only() {
+            self.set_status("Read-only, log in to save the attendance");
+            return;
+        }
+        let Some(attendance) = self.attendance.take() else {
+            return;
+        };
+        let anwesend: Vec<&String> = attendance
+            .members
+            .items
+            .iter()
+            .filter(|(_, present)| *present)
+            .map(|(name, _)| name)
+            .collect();
+        let mut data = serde_json::json!({
+            "id": attendance.sitzung,
+            "anwesend": anwesend,
+        });
+        if let Some(version) = attendance.version {
+            data["updated_at"] = serde_json::Value::String(version);
+        }
+        let url = format!("{}api/topmanager/sitzung/", base_url());
+        self.send_mutation(Method::PATCH, url, Some(data), Reload::Sitzungen);
+        self.set_status(format!("Saving attendance, {} present…", anwesend.len()));
+    }
+
+    fn open_profile_switcher(&mut self) {
+        if self.config.profiles.is_empty() {
+            self.set_status("No profiles configured");
+            return;
+        }
+        let profiles = std::iter::once(None)
+            .chain(self.config.profiles.keys().cloned().map(Some))
+            .collect();
+        let mut switcher = StatefulList::with_items(profiles);
+        let current = switcher.items.iter().position(|p| *p == self.profile);
+        switcher.state.select(current);
+        self.profile_switcher = Some(switcher);
+    }
+
+    fn handle_profile_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(switcher) = &mut self.profile_switcher else {
+            return;
+        };
+        match key.code {
+            Char('q') | Esc => self.profile_switcher = None,
+            Char('j') | Down => switcher.next(),
+            Char('k') | Up => switcher.previous(),
+            Enter => {
+                if let Some(profile) = switcher.selected_item().cloned() {
+                    self.profile_switcher = None;
+                    self.switch_profile(profile);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Points the app at another backend and starts over there: everything
+    /// loaded from the old one is dropped, then it logs in and reloads.
+    fn switch_profile(&mut self, name: Option<String>) {
+        let Some(profile) = self.config.profile(name.as_deref()) else {
+            return;
+        };
+        point_at(profile.url.as_deref());
+        self.insecure = profile.insecure && base_url() != URL;
+        self.backend = backend::Backend::spawn(base_url(), self.insecure, profile.auth);
+        self.profile = name;
+        // The old token belongs to the old backend, until the new login
+        // succeeds the app is read-only.
+        self.token = None;
+        self.token_expires = None;
+        self.user = None;
+
+        self.clear_filter();
+        self.layout = SelectedLayout::Sitzungen;
+        self.focus = Pane::List;
+        self.all_sitzungen.clear();
+        self.sitzungen = StatefulList::with_items(vec![]);
+        self.tops_selected_sitzung = StatefulList::with_items(vec![]);
+        self.anträge_selected_top = StatefulList::with_items(vec![]);
+        self.sitzung = Sitzung::default();
+        self.top = Top::default();
+        self.tops_cache.clear();
+        self.prefetching.clear();
+        self.prefetch_due = None;
+        self.antrag_counts.clear();
+        self.counting.clear();
+        self.awaiting_tops = None;
+        self.awaiting_anträge = None;
+        self.awaiting_sitzungen = false;
+        self.loading = None;
+        self.select_created = None;
+        self.edit_created = false;
+        self.last_failed = None;
+        // Batches still running finish on the old backend, their answers
+        // never arrive.
+        self.renumbering = false;
+        self.import = None;
+        self.merge = None;
+        self.history = History::default();
+        if self.dashboard.is_some() {
+            self.dashboard = Some(Dashboard::default());
+        }
+
+        self.version_warning = None;
+        self.backend.send(Request::Login);
+        self.backend.send(Request::Version);
+        self.load_sitzungen();
+        let label = self.profile.as_deref().unwrap_or("default");
+        self.set_status(format!("Switched to {}, logging in…", label));
+    }
+
+    /// Opens a recently visited Sitzung or Top, wherever the app currently is.
+    fn jump_to(&mut self, visit: session::Visit) {
+        let Some(index) = self
+            .all_sitzungen
+            .iter()
+            .position(|s| s.id == visit.sitzung_id)
+        else {
+            self.set_status(format!("'{}' no longer exists", visit.sitzung));
+            return;
+        };
+        if !self
+            .sitzungen
+            .items
+            .iter()
+            .any(|s| s.id == visit.sitzung_id)
+        {
+            self.show_past = true;
+            self.clear_filter();
+            self.refresh_visible_sitzungen();
+        }
+        let sitzung = self.all_sitzungen[index].clone();
+        let position = self.sitzungen.items.iter().position(|s| s.id == sitzung.id);
+        self.sitzungen.state.select(position);
+        self.layout = SelectedLayout::Sitzungen;
+        self.open_sitzung();
+        if let Some(top) = visit.top.clone() {
+            let position = self
+                .tops_selected_sitzung
+                .items
+                .iter()
+                .position(|t| t.id == top.id);
+            if position.is_some() {
+                self.tops_selected_sitzung.state.select(position);
+            }
+            self.remember_visit(visit);
+            self.top = Top {
+                id: top.id,
+                name: top.name,
+                ..Top::default()
+            };
+            self.set_anträge(vec![]);
+            self.load_anträge(0);
+            self.layout = SelectedLayout::Anträge;
+        }
+    }
+
+    fn start_filter(&mut self) {
+        self.clear_filter();
+        let unfiltered = match self.layout {
+            SelectedLayout::Sitzungen => Unfiltered::Sitzungen,
+            SelectedLayout::Tops => Unfiltered::Tops(self.tops_selected_sitzung.items.clone()),
+            SelectedLayout::Anträge => {
+                Unfiltered::Anträge(self.anträge_selected_top.items.clone())
+            }
+        };
+        self.filter = Some(Filter {
+            input: TextArea::default(),
+            typing: true,
+            unfiltered,
+        });
+    }
+
+    /// Shows the matches for the current query, best match selected.
+    fn apply_filter(&mut self) {
+        let Some(filter) = &self.filter else {
+            return;
+        };
+        let query = filter.query();
+        match &filter.unfiltered {
+            Unfiltered::Sitzungen => {
+                self.sitzungen = StatefulList::with_items(self.visible_sitzungen());
+            }
+            Unfiltered::Tops(all) => {
+                let tops = fuzzy_rank(all.clone(), &query, |t| &t.name);
+                self.tops_selected_sitzung = StatefulList::with_items(tops);
+            }
+            Unfiltered::Anträge(all) => {
+                let anträge = fuzzy_rank(all.clone(), &query, |a| &a.titel);
+                self.anträge_selected_top = StatefulList::with_items(anträge);
+            }
+        }
+    }
+
+    /// Brings back the full list the filter was started on, keeping the
+    /// selected item.
+    fn clear_filter(&mut self) {
+        let Some(filter) = self.filter.take() else {
+            return;
+        };
+        match filter.unfiltered {
+            Unfiltered::Sitzungen => self.refresh_visible_sitzungen(),
+            Unfiltered::Tops(all) => {
+                let selected = self.tops_selected_sitzung.selected_item().map(|t| t.id);
+                self.tops_selected_sitzung = StatefulList::with_items(all);
+                self.tops_selected_sitzung
+                    .sort_keeping_selection(|t| t.id, compare_tops(self.tops_sort));
+                let index = self
+                    .tops_selected_sitzung
+                    .items
+                    .iter()
+                    .position(|t| Some(t.id) == selected);
+                self.tops_selected_sitzung.state.select(index.or(Some(0)));
+            }
+            Unfiltered::Anträge(all) => {
+                let selected = self.anträge_selected_top.selected_item().map(|a| a.id);
+                self.anträge_selected_top = StatefulList::with_items(all);
+                self.anträge_selected_top
+                    .sort_keeping_selection(|a| a.id, compare_anträge(self.anträge_sort));
+                let index = self
+                    .anträge_selected_top
+                    .items
+                    .iter()
+                    .position(|a| Some(a.id) == selected);
+                self.anträge_selected_top.state.select(index.or(Some(0)));
+            }
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        let Some(filter) = &mut self.filter else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.clear_filter(),
+            KeyCode::Enter => filter.typing = false,
+            KeyCode::Down => self.selected_list_next(),
+            KeyCode::Up => self.selected_list_previous(),
+            _ => {
+                if filter.input.input(key) {
+                    self.apply_filter();
+                }
+            }
+        }
+    }
+
+    fn selected_list_next(&mut self) {
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.next(),
+            SelectedLayout::Tops => self.tops_selected_sitzung.next(),
+            SelectedLayout::Anträge => self.anträge_selected_top.next(),
+        }
+    }
+
+    fn selected_list_previous(&mut self) {
+        match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.previous(),
+            SelectedLayout::Tops => self.tops_selected_sitzung.previous(),
+            SelectedLayout::Anträge => self.anträge_selected_top.previous(),
+        }
+    }
+
+    /// Indices of the characters in `text` matched by the active filter.
+    fn filter_matches(&self, text: &str) -> Vec<usize> {
+        match &self.filter {
+            Some(filter) if filter.layout() == self.layout => SkimMatcherV2::default()
+                .fuzzy_indices(text, &filter.query())
+                .map(|(_, indices)| indices)
+                .unwrap_or_default(),
+            _ => vec![],
+        }
+    }
+
+    fn open_trash(&mut self) {
+        let url = match self.layout {
+            SelectedLayout::Sitzungen => format!("{}api/topmanager/sitzungen/", base_url()),
+            SelectedLayout::Tops => {
+                format!(
+                    "{}api/topmanager/sitzung/{}/tops/",
+                    base_url(),
+                    self.sitzung.id
+                )
+            }
+            SelectedLayout::Anträge => {
+                format!("{}api/topmanager/tops/{}/anträge/", base_url(), self.top.id)
+            }
+        };
+        let url = format!("{}?deleted=true", url);
+        self.backend.send(Request::Trash(url.clone()));
+        self.trash = Some(Trash {
+            layout: self.layout.clone(),
+            url,
+            items: StatefulList::with_items(vec![]),
+            loading: true,
+        });
+    }
+
+    fn restore_deleted(&mut self) {
+        let Some(trash) = &mut self.trash else {
+            return;
+        };
+        let Some(index) = trash.items.state.selected() else {
+            return;
+        };
+        if index >= trash.items.items.len() {
+            return;
+        }
+        let deleted = trash.items.items.remove(index);
+        trash.items.select_nearest(index);
+        let (url, reload) = match trash.layout {
+            SelectedLayout::Sitzungen => (
+                format!("{}api/topmanager/sitzung/", base_url()),
+                Reload::Sitzungen,
+            ),
+            SelectedLayout::Tops => (format!("{}api/topmanager/top/", base_url()), Reload::Tops),
+            SelectedLayout::Anträge => (
+                format!("{}api/topmanager/antrag/", base_url()),
+                Reload::Anträge,
+            ),
+        };
+        let json = serde_json::json!({ "id": deleted.id, "deleted": false });
+        self.send_mutation(Method::PATCH, url, Some(json), reload);
+        self.set_status(format!("Restoring '{}'", deleted.name));
+    }
+
+    fn handle_trash_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(trash) = &mut self.trash else {
+            return;
+        };
+        match key.code {
+            Char('q') | Char('t') | Esc => self.trash = None,
+            Char('j') | Down => trash.items.next(),
+            Char('k') | Up => trash.items.previous(),
+            Char('r') | Enter if !self.read_only() => self.restore_deleted(),
+            _ => {}
+        }
+    }
+
+    fn start_import(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let mut prompt = TextArea::default();
+        prompt.set_block(Block::default().title(tr(self.config.lang, "Path to a Markdown file")));
+        self.import = Some(ImportStep::Prompt(Box::new(prompt)));
+    }
+
+    fn read_import_file(&mut self, path: &str) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => self.import = Some(ImportStep::Confirm(import::parse(&text))),
+            Err(err) => {
+                self.import = None;
+                self.set_status(format!("Could not read {}: {}", path, err));
+            }
+        }
+    }
+
+    fn import_anträge(&mut self, entwürfe: Vec<Result<import::Entwurf, String>>) {
+        let Some(token) = self.token.clone() else {
+            self.import = None;
+            return;
+        };
+        let (entwürfe, unreadable) = split_unreadable(entwürfe);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let url = format!("{}api/topmanager/top/{}/antrag/", base_url(), self.top.id);
+        self.import = Some(ImportStep::Running {
+            done: 0,
+            total: entwürfe.len(),
+            titel: String::new(),
+            cancel: cancel.clone(),
+            unreadable,
+        });
+        self.backend.send(Request::Import {
+            url,
+            token,
+            entwürfe,
+            cancel,
+        });
+    }
+
+    fn show_import_report(&mut self, results: Vec<Result<String, String>>) {
+        let (total, unreadable) = match self.import.take() {
+            Some(ImportStep::Running {
+                total, unreadable, ..
+            }) => (total, unreadable),
+            _ => (results.len(), vec![]),
+        };
+        let created = results.iter().filter(|result| result.is_ok()).count();
+        let failed = results.len() - created + unreadable.len();
+        let skipped = total - results.len();
+        let mut report: Vec<String> = results
+            .iter()
+            .map(|result| match result {
+                Ok(titel) => format!("✓ {}", titel),
+                Err(err) => format!("✗ {}", err),
+            })
+            .chain(unreadable.iter().map(|err| format!("✗ {}", err)))
+            .collect();
+        report.push(String::new());
+        let lang = self.config.lang;
+        let mut summary = fill(tr(lang, "{} created, {} failed"), &[&created, &failed]);
+        if skipped > 0 {
+            let template = tr(lang, ", {} skipped after cancelling");
+            summary.push_str(&fill(template, &[&skipped]));
+        }
+        report.push(summary);
+        self.import = Some(ImportStep::Report(report.join("\n")));
+        self.reload(Reload::Anträge);
+    }
+
+    fn start_merge(&mut self) {
+        if self.read_only() {
+            return;
+        }
+        let Some(source) = self.sitzungen.selected_item().cloned() else {
+            return;
+        };
+        let mut targets: Vec<Sitzung> = self
+            .all_sitzungen
+            .iter()
+            .filter(|sitzung| sitzung.id != source.id)
+            .cloned()
+            .collect();
+        if targets.is_empty() {
+            self.set_status("There is no other Sitzung to merge into");
+            return;
+        }
+        targets.sort_by_key(|sitzung| sitzung.datum);
+        self.merge = Some(MergeStep::Target {
+            source,
+            targets: StatefulList::with_items(targets),
+        });
+    }
+
+    fn handle_merge_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        match self.merge.as_mut() {
+            Some(MergeStep::Target { source, targets }) => match key.code {
+                Char('q') | Esc => self.merge = None,
+                Char('j') | Down => targets.next(),
+                Char('k') | Up => targets.previous(),
+                Enter => {
+                    if let Some(target) = targets.selected_item().cloned() {
+                        let source = source.clone();
+                        self.merge = Some(MergeStep::Confirm { source, target });
+                    }
+                }
+                _ => {}
+            },
+            Some(MergeStep::Confirm { .. }) => match key.code {
+                Char('y') | Enter => {
+                    if let Some(MergeStep::Confirm { source, target }) = self.merge.take() {
+                        self.merge_sitzungen(source, target);
+                    }
+                }
+                Char('n') | Esc => self.merge = None,
+                _ => {}
+            },
+            Some(MergeStep::Running { .. }) => {}
+            Some(MergeStep::Report { empty, .. }) => {
+                let source = empty.take();
+                self.merge = None;
+                if let (Some(source), Char('y')) = (source, key.code) {
+                    let url = format!("{}api/topmanager/sitzung/", base_url());
+                    let json = serde_json::json!({ "id": source.id });
+                    self.log(format!("Deleting Sitzung \"{}\"", source.name));
+                    self.send_mutation(Method::DELETE, url, Some(json), Reload::Sitzungen);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn merge_sitzungen(&mut self, source: Sitzung, target: Sitzung) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        self.log(format!(
+            "Moving the Tops of \"{}\" to \"{}\"",
+            source.name, target.name
+        ));
+        self.backend.send(Request::Merge {
+            source: source.id,
+            target: target.id,
+            token,
+        });
+        self.merge = Some(MergeStep::Running {
+            source,
+            done: 0,
+            total: 0,
+            titel: String::new(),
+        });
+    }
+
+    fn show_merge_report(&mut self, report: MergeReport) {
+        let source = match self.merge.take() {
+            Some(MergeStep::Running { source, .. }) => Some(source),
+            _ => None,
+        };
+        let lang = self.config.lang;
+        let moved = tr(lang, "{} of {} Tops moved");
+        let mut text = fill(moved, &[&report.moved, &report.total]);
+        for failure in &report.failures {
+            text.push_str(&format!("\n✗ {}", failure));
+        }
+        let empty = source.filter(|_| report.failures.is_empty());
+        if let Some(source) = &empty {
+            let question = tr(
+                lang,
+                "'{}' is empty now. Delete it? y to delete, any other key to keep",
+            );
+            text.push_str("\n\n");
+            text.push_str(&fill(question, &[&source.name]));
+        }
+        self.log(text.lines().next().unwrap_or_default().to_string());
+        self.merge = Some(MergeStep::Report {
+            report: text,
+            empty,
+        });
+        self.reload(Reload::All);
+    }
+
+    fn handle_import_key(&mut self, key: KeyEvent) {
+        match self.import.as_mut() {
+            Some(ImportStep::Prompt(prompt)) => match key.code {
+                KeyCode::Esc => self.import = None,
+                KeyCode::Enter => {
+                    let path = prompt.lines().join("");
+                    self.read_import_file(path.trim());
+                }
+                _ => {
+                    prompt.input(key);
+                }
+            },
+            Some(ImportStep::Confirm(_)) => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => {
+                    if let Some(ImportStep::Confirm(entwürfe)) = self.import.take() {
+                        self.import_anträge(entwürfe);
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Esc => self.import = None,
+                _ => {}
+            },
+            Some(ImportStep::Running { cancel, .. }) => {
+                if let KeyCode::Esc | KeyCode::Char('c') = key.code {
+                    cancel.store(true, atomic::Ordering::Relaxed);
+                    self.set_status("Cancelling import…");
+                }
+            }
+            Some(ImportStep::Report(_)) => self.import = None,
+            None => {}
+        }
+    }
+
+    fn edit_value(&mut self) {
+        let Some(param) = self.edit_buffer.selected_item().cloned() else {
+            return;
+        };
+        self.edit_param_pop = Some(param);
+        let param = self.edit_param_pop.as_ref().unwrap();
+        let text = &param.text;
+        self.current_text_area = TextArea::default();
+        self.current_text_area.insert_str(text);
+        if self.config.select_on_edit {
+            self.current_text_area.select_all();
+        }
+    }
+
+    /// Quits, or first asks what to do with unsaved edits.
+    fn exit_app(&mut self) {
+        if self.edit_param_pop.is_some() {
+            self.update();
+            self.edit_param_pop = None;
+        }
+        let editing = self.currently_editing.is_some() || self.currently_creating.is_some();
+        let notes = self.notes.as_ref().is_some_and(|n| n.changed.is_some());
+        if (editing && self.has_unsaved_changes()) || notes {
+            self.confirm_quit = true;
+        } else {
+            self.quit = true;
+        }
+    }
+
+    fn handle_quit_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('s') | KeyCode::Char('y') | KeyCode::Enter => self.save_and_quit(),
+            KeyCode::Char('d') | KeyCode::Char('n') => self.quit = true,
+            KeyCode::Char('c') | KeyCode::Esc => self.confirm_quit = false,
+            _ => {}
+        }
+    }
+
+    /// Sends the unsaved edits, the app quits once the backend took them.
+    fn save_and_quit(&mut self) {
+        self.confirm_quit = false;
+        self.quit_after_save = true;
+        if self.notes.is_some() {
+            self.close_notes();
+        } else {
+            self.exit_edit();
+        }
+        self.set_status("Saving before quitting…");
+    }
+
+    fn finish_quit(&mut self, saved: bool) {
+        if std::mem::take(&mut self.quit_after_save) {
+            if saved {
+                self.quit = true;
+            } else {
+                self.set_status("Saving failed, not quitting");
+            }
+        }
+    }
+
+    fn build_payload(&self) -> serde_json::Value {
+        edit_payload(
+            self.currently_editing.as_ref(),
+            &self.sitzung,
+            self.tops_selected_sitzung.selected_item(),
+            &self.antrag,
+            &self.edit_buffer.items,
+            self.edit_version.as_deref(),
+        )
+    }
+
+    fn preview_payload(&mut self) {
+        let data = self.build_payload();
+        self.payload_preview = Some(serde_json::to_string_pretty(&data).unwrap());
+    }
+
+    fn patch(&mut self) {
+        let url = match self.currently_editing {
+            Some(SelectedLayout::Sitzungen) => format!("{}api/topmanager/sitzung/", base_url()),
+            Some(SelectedLayout::Tops) => format!("{}api/topmanager/top/", base_url()),
+            Some(SelectedLayout::Anträge) => format!("{}api/topmanager/antrag/", base_url()),
+            None => return,
+        };
+        let data = self.build_payload();
+        self.last_patch = self
+            .edited_item()
+            .map(|(layout, id)| (layout, id, self.edit_buffer.items.clone()));
+        self.saving = self.edited_item().map(|(_, id)| Saving {
+            url: url.clone(),
+            id,
+            params: self.edit_buffer.items.clone(),
+        });
+        self.send_mutation(Method::PATCH, url, Some(data), Reload::All);
+    }
+
+    /// The kind and id of the item in the editor.
+    fn edited_item(&self) -> Option<(SelectedLayout, Uuid)> {
+        let layout = self.currently_editing.clone()?;
+        let id = match layout {
+            SelectedLayout::Sitzungen => self.sitzung.id,
+            SelectedLayout::Tops => self.tops_selected_sitzung.selected_item()?.id,
+            SelectedLayout::Anträge => self.antrag.id,
+        };
+        Some((layout, id))
+    }
+
+    /// Fetches the edited item again to learn its new version after a save.
+    fn refetch_edited(&mut self) {
+        match self.edited_item() {
+            Some((SelectedLayout::Sitzungen, id)) => self.backend.send(Request::Sitzung(id)),
+            Some((SelectedLayout::Tops, id)) => self.backend.send(Request::Top(id)),
+            Some((SelectedLayout::Anträge, id)) => self.backend.send(Request::Antrag(id)),
+            None => {}
+        }
+    }
+
+    fn update_edit_version(&mut self, id: Uuid, version: Option<String>) {
+        if self.edited_item().is_some_and(|(_, edited)| edited == id) {
+            self.edit_version = version;
+            self.edit_fetched = Some(Instant::now());
+        }
+    }
+
+    /// Holds back saving if the edited item was fetched too long ago and
+    /// there is no version to let the backend catch a conflict.
+    fn hold_stale_save(&mut self, leave: bool) -> bool {
+        let stale = self
+            .edit_fetched
+            .is_some_and(|fetched| fetched.elapsed() > self.config.stale_edit_after());
+        if self.currently_editing.is_some() && self.edit_version.is_none() && stale {
+            self.stale_save = Some(leave);
+        }
+        self.stale_save.is_some()
+    }
+
+    fn handle_stale_key(&mut self, key: KeyEvent) {
+        let Some(leave) = self.stale_save else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                self.stale_save = None;
+                self.saving = None;
+                self.quit_after_save = false;
+                self.last_patch = self
+                    .edited_item()
+                    .map(|(layout, id)| (layout, id, self.edit_buffer.items.clone()));
+                self.reload_and_reapply();
+            }
+            KeyCode::Char('n') => {
+                self.stale_save = None;
+                self.edit_fetched = Some(Instant::now());
+                if leave {
+                    self.exit_edit();
+                } else {
+                    self.save_edit();
+                }
+            }
+            KeyCode::Esc => {
+                self.stale_save = None;
+                self.saving = None;
+                self.quit_after_save = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Fetches the server version of the conflicting item, the editor then
+    /// opens with the refused edits applied on top of it.
+    fn reload_and_reapply(&mut self) {
+        self.conflict = false;
+        let Some((layout, id, mine)) = self.last_patch.take() else {
+            return;
+        };
+        self.close_editor();
+        self.reapply = Some(mine);
+        self.backend.send(match layout {
+            SelectedLayout::Sitzungen => Request::Sitzung(id),
+            SelectedLayout::Tops => Request::Top(id),
+            SelectedLayout::Anträge => Request::Antrag(id),
+        });
+    }
+
+    fn reapply_edits(&mut self) {
+        let Some(mine) = self.reapply.take() else {
+            return;
+        };
+        for param in &mut self.edit_buffer.items {
+            if let Some(edited) = mine.iter().find(|edited| edited.key == param.key) {
+                param.text = edited.text.clone();
+            }
+        }
+        self.show_diff = true;
+        self.set_status("Your changes on top of the server version, Ctrl+S to save");
+    }
+
+    fn handle_conflict_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => self.reload_and_reapply(),
+            KeyCode::Char('n') | KeyCode::Esc => self.conflict = false,
+            _ => {}
+        }
+    }
+
+    fn put(&mut self) {
+        let (url, reload) = match self.currently_creating {
+            Some(SelectedLayout::Sitzungen) => (
+                format!("{}api/topmanager/sitzung/", base_url()),
+                Reload::Sitzungen,
+            ),
+            Some(SelectedLayout::Tops) => return self.put_top(),
+            Some(SelectedLayout::Anträge) => (
+                format!("{}api/topmanager/top/{}/antrag/", base_url(), self.top.id),
+                Reload::Anträge,
+            ),
+            None => return,
+        };
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        self.backend.send(Request::Create(Mutation {
+            method: Method::PUT,
+            url,
+            body: Some(self.build_payload()),
+            token,
+            reload,
+        }));
+    }
+
+    fn put_top(&mut self) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        let anträge = inline_antrag_payloads(&self.edit_buffer.items)
+            .into_iter()
+            .map(serde_json::Value::Object)
+            .collect();
+        self.backend.send(Request::CreateTop {
+            sitzung_id: self.sitzung.id,
+            token,
+            top: self.build_payload(),
+            anträge,
+        });
+    }
+
+    fn add_inline_antrag(&mut self) {
+        let number = inline_antrag_payloads(&self.edit_buffer.items).len() + 1;
+        for (titel, key) in [
+            ("Titel", "titel"),
+            ("Begründung", "begründung"),
+            ("Antragstext", "antragstext"),
+        ] {
+            self.edit_buffer.items.push(Param::new(
+                &format!("Antrag {}: {}", number, titel),
+                &format!("antrag.{}.{}", number, key),
+                "",
+            ));
+        }
+        self.apply_templates();
+    }
+
+    /// Takes the inline Antrag under the cursor out of the Top being created.
+    fn remove_inline_antrag(&mut self) {
+        let Some(selected) = self.edit_buffer.state.selected() else {
+            return;
+        };
+        if remove_inline_antrag(&mut self.edit_buffer.items, selected) {
+            self.edit_buffer.select_nearest(selected);
+        }
+    }
+
+    /// Gives the Tops the weights 0, 1, 2, … in the order they are listed.
+    fn renumber_tops(&mut self) {
+        if self.renumbering {
+            return;
+        }
+        let Some(token) = self.token.clone() else {
+            self.set_status("Read-only, log in to renumber");
+            return;
+        };
+        if self.filter.is_some() {
+            self.set_status("Clear the filter first, all Tops have to be listed");
+            return;
+        }
+        let weights = renumbered_weights(&self.tops_selected_sitzung.items);
+        if weights.is_empty() {
+            self.set_status("Weights are already consecutive");
+            return;
+        }
+        self.renumbering = true;
+        self.set_status(format!("Renumbering Tops… 0/{}", weights.len()));
+        self.backend.send(Request::Renumber {
+            sitzung_id: self.sitzung.id,
+            token,
+            weights,
+        });
+    }
+
+    fn show_renumbered(&mut self, results: Vec<Result<(), String>>) {
+        self.renumbering = false;
+        let done = results.iter().filter(|result| result.is_ok()).count();
+        let mut message = format!("Renumbered {} of {} Tops", done, results.len());
+        if let Some(Err(err)) = results.iter().find(|result| result.is_err()) {
+            message.push_str(&format!(" ({})", err));
+        }
+        self.set_status(message);
+        self.reload(Reload::Tops);
+    }
+
+    fn show_top_created(&mut self, top: Result<Uuid, AppError>, anträge: Vec<Result<(), String>>) {
+        match top {
+            Ok(id) if self.layout == SelectedLayout::Tops => {
+                self.select_created = Some(id);
+                self.open_tops_only = false;
+            }
+            Ok(_) => {}
+            Err(_) => self.select_created = None,
+        }
+        match top {
+            Ok(_) if anträge.is_empty() => self.set_status("Top created"),
+            Ok(_) => {
+                let created = anträge.iter().filter(|result| result.is_ok()).count();
+                let mut message =
+                    format!("Top created with {} of {} Anträge", created, anträge.len());
+                if let Some(Err(err)) = anträge.iter().find(|result| result.is_err()) {
+                    message.push_str(&format!(" ({})", err));
+                }
+                self.set_status(message);
+            }
+            Err(err) => self.show_error("Creating the Top", &err, false),
+        }
+        self.reload(Reload::Tops);
+    }
+
+    fn update(&mut self) {
+        let mut value = self.current_text_area.lines().join("\n");
+        let Some(param) = self.edit_buffer.selected_item() else {
+            return;
+        };
+        let selected = self.edit_buffer.state.selected().unwrap_or_default();
+        if param.key == "datum" {
+            match datum::parse(&value, Local::now().naive_local()) {
+                Some(datum) => value = datum.to_string(),
+                None => self.set_status(format!("Could not read '{}' as a date", value)),
+            }
+        }
+        self.edit_buffer.items[selected].text = value;
+    }
+}
+
+impl App<'_> {
+    fn run(&mut self, mut terminal: Terminal<impl Backend>) -> io::Result<()> {
+        loop {
+            self.receive_responses();
+            if self.quit {
+                return Ok(());
+            }
+            crash::record(self.crash_context());
+            self.draw(&mut terminal)?;
+            if !event::poll(TICK_RATE)? {
+                self.on_tick();
+                continue;
+            }
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+                Event::Resize(_, _) => terminal.autoresize()?,
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, terminal: &mut Terminal<impl Backend>) -> io::Result<()> {
+        terminal.draw(|f| f.render_widget(self, f.size()))?;
+        Ok(())
+    }
+
+    /// The actions available right now, matching the dispatch in `handle_key`.
+    fn footer_hints(&self) -> Vec<Hint> {
+        if self.confirm_quit {
+            return vec![
+                ("s", "save and quit"),
+                ("d", "discard and quit"),
+                ("Esc", "cancel"),
+            ];
+        }
+        if self.notes.is_some() {
+            return vec![("Esc", "save and close")];
+        }
+        if self.filter.as_ref().is_some_and(|filter| filter.typing) {
+            return vec![("↓↑", "move"), ("Enter", "done"), ("Esc", "clear filter")];
+        }
+        if self.quick_create.is_some() {
+            return vec![("Enter", "create"), ("Esc", "cancel")];
+        }
+        if self.rename.is_some() {
+            return vec![("Enter", "rename"), ("Esc", "cancel")];
+        }
+        match &self.author_search {
+            Some(AuthorSearch::Prompt(_)) => return vec![("Enter", "search"), ("Esc", "cancel")],
+            Some(AuthorSearch::Searching(_)) => return vec![("q", "cancel")],
+            Some(AuthorSearch::Results(..)) => {
+                return vec![("↓↑", "move"), ("Enter", "open"), ("q", "close")]
+            }
+            None => {}
+        }
+        if self.edit_param_pop.is_some() {
+            return vec![
+                ("Esc", "done"),
+                ("Ctrl+U", "clear"),
+                ("Ctrl+A/Ctrl+E", "line start/end"),
+                ("F11", "full screen"),
+            ];
+        }
+        if let Some(step) = &self.merge {
+            return match step {
+                MergeStep::Target { .. } => {
+                    vec![("↓↑", "move"), ("Enter", "merge into"), ("q", "cancel")]
+                }
+                MergeStep::Confirm { .. } => vec![("y", "merge"), ("n", "cancel")],
+                MergeStep::Running { .. } => vec![],
+                MergeStep::Report { empty: Some(_), .. } => {
+                    vec![("y", "delete empty Sitzung"), ("any key", "close")]
+                }
+                MergeStep::Report { empty: None, .. } => vec![("any key", "close")],
+            };
+        }
+        if let Some(step) = &self.import {
+            return match step {
+                ImportStep::Prompt(_) => vec![("Enter", "read file"), ("Esc", "cancel")],
+                ImportStep::Confirm(_) => vec![("y", "import"), ("n", "cancel")],
+                ImportStep::Running { .. } => vec![("c", "cancel")],
+                ImportStep::Report(_) => vec![("any key", "close")],
+            };
+        }
+        if self.conflict {
+            return vec![("y", "reload and reapply"), ("n", "dismiss")];
+        }
+        if self.stale_save.is_some() {
+            return vec![
+                ("y", "fetch again"),
+                ("n", "save anyway"),
+                ("Esc", "cancel"),
+            ];
+        }
+        if self.trash.is_some() {
+            return TRASH_HINTS.to_vec();
+        }
+        if self.raw_json.is_some() {
+            return vec![
+                ("↓↑", "scroll"),
+                ("y", "copy"),
+                ("w", "wrap"),
+                ("q", "close"),
+            ];
+        }
+        if self.agenda_summary.is_some() {
+            return vec![("y", "copy"), ("any key", "close")];
+        }
+        if self.protocol.is_some() {
+            return vec![
+                ("↓↑", "scroll"),
+                ("y", "copy Markdown"),
+                ("w", "wrap"),
+                ("q", "close"),
+            ];
+        }
+        if self.switcher.is_some() {
+            return vec![("↓↑", "move"), ("Enter", "open"), ("q", "close")];
+        }
+        if self.profile_switcher.is_some() {
+            return vec![("↓↑", "move"), ("Enter", "switch"), ("q", "close")];
+        }
+        if self.anhang_picker.is_some() {
+            return vec![("↓↑", "move"), ("Enter", "open"), ("q", "close")];
+        }
+        if self.dashboard.is_some() {
+            return vec![("r", "refresh"), ("q", "close")];
+        }
+        if self.attendance.is_some() {
+            return vec![
+                ("↓↑", "move"),
+                ("Space", "present/absent"),
+                ("Enter", "save"),
+                ("q", "cancel"),
+            ];
+        }
+        if self.payload_preview.is_some() {
+            return vec![("w", "wrap"), ("any key", "close")];
+        }
+        if self.show_diff {
+            return vec![("any key", "close")];
+        }
+        if self.confirm_leave {
+            return vec![("y", "save"), ("n", "discard"), ("Esc", "keep editing")];
+        }
+        let mut hints = if self.currently_editing.is_some() {
+            EDIT_HINTS.to_vec()
+        } else if let Some(creating) = &self.currently_creating {
+            let mut hints = CREATE_HINTS.to_vec();
+            if *creating == SelectedLayout::Tops {
+                hints.insert(2, ("a", "add Antrag"));
+                hints.insert(3, ("x", "remove Antrag"));
+            }
+            hints
+        } else {
+            match self.layout {
+                SelectedLayout::Anträge if self.focus == Pane::Detail => {
+                    vec![
+                        ("↓↑", "scroll"),
+                        ("z", "fold section"),
+                        ("Z", "fold all"),
+                        ("w", "wrap"),
+                        ("Tab", "back to the list"),
+                    ]
+                }
+                SelectedLayout::Sitzungen => SITZUNGEN_HINTS.to_vec(),
+                SelectedLayout::Tops => TOPS_HINTS.to_vec(),
+                SelectedLayout::Anträge => ANTRÄGE_HINTS.to_vec(),
+            }
+        };
+        if self.last_failed.is_some() {
+            hints.insert(0, ("R", "retry failed request"));
+        }
+        hints.extend_from_slice(GLOBAL_HINTS);
+        hints
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if self.confirm_quit {
+            self.handle_quit_key(key);
+            return;
+        }
+        if key.code == KeyCode::Char('q') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.exit_app();
+            return;
+        }
+        if self.edit_param_pop.is_some() {
+            self.handle_text_area(key);
+            return;
+        }
+        if self.import.is_some() {
+            self.handle_import_key(key);
+            return;
+        }
+        if self.notes.is_some() {
+            self.handle_notes_key(key);
+            return;
+        }
+        if self.filter.as_ref().is_some_and(|filter| filter.typing) {
+            self.handle_filter_key(key);
+            return;
+        }
+        if self.quick_create.is_some() {
+            self.handle_quick_create_key(key);
+            return;
+        }
+        if self.rename.is_some() {
+            self.handle_rename_key(key);
+            return;
+        }
+        if let Some(AuthorSearch::Prompt(_)) = self.author_search {
+            self.handle_author_prompt_key(key);
+            return;
+        }
+        if self.type_ahead.is_some() && self.handle_type_ahead_key(key) {
+            return;
+        }
+        let key = self.keymap.translate(key);
+        if is_navigation(&key) && !self.key_repeat.accept(key.code, Instant::now()) {
+            return;
+        }
+        if self.focus_mode.is_some() {
+            self.handle_focus_key(key);
+            return;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let layout = self.layout.clone();
+        let view = self.current_view();
+        let in_history = alt && matches!(key.code, KeyCode::Left | KeyCode::Right);
+        if ctrl && key.code == KeyCode::Char('r') {
+            self.reload_config();
+        } else if ctrl && key.code == KeyCode::Char('t') {
+            self.backend.send(Request::Login);
+            self.set_status("Logging in…");
+        } else if ctrl && key.code == KeyCode::Char('g') {
+            self.config.lang = self.config.lang.toggle();
+            self.set_status(self.config.lang.name());
+        } else if self.conflict {
+            self.handle_conflict_key(key);
+        } else if self.stale_save.is_some() {
+            self.handle_stale_key(key);
+        } else if self.merge.is_some() {
+            self.handle_merge_key(key);
+        } else if self.trash.is_some() {
+            self.handle_trash_key(key);
+        } else if self.raw_json.is_some() {
+            self.handle_raw_json_key(key);
+        } else if self.agenda_summary.is_some() {
+            self.handle_agenda_summary_key(key);
+        } else if self.protocol.is_some() {
+            self.handle_protocol_key(key);
+        } else if self.switcher.is_some() {
+            self.handle_switcher_key(key);
+        } else if self.profile_switcher.is_some() {
+            self.handle_profile_key(key);
+        } else if self.anhang_picker.is_some() {
+            self.handle_anhang_key(key);
+        } else if self.attendance.is_some() {
+            self.handle_attendance_key(key);
+        } else if self.dashboard.is_some() {
+            self.handle_dashboard_key(key);
+        } else if self.author_search.is_some() {
+            self.handle_author_results_key(key);
+        } else if self.payload_preview.is_some() {
+            self.handle_payload_preview(key);
+        } else if self.show_diff {
+            self.show_diff = false;
+        } else if self.confirm_leave {
+            self.handle_leave_key(key);
+        } else if key.code == KeyCode::Char('R') && self.last_failed.is_some() {
+            self.retry_failed();
+        } else if self.currently_editing.is_some() || self.currently_creating.is_some() {
+            self.handle_edit(key);
+        } else if ctrl && key.code == KeyCode::Char('p') {
+            self.open_switcher();
+        } else if ctrl && key.code == KeyCode::Char('w') {
+            self.open_profile_switcher();
+        } else if ctrl && key.code == KeyCode::Char('l') {
+            self.show_log = !self.show_log;
+        } else if key.code == KeyCode::Char('F') {
+            self.focus_mode = Some(0);
+        } else if in_history {
+            self.move_in_history(key.code == KeyCode::Right);
+        } else if key.code == KeyCode::Esc && self.loading.is_some() {
+            self.cancel_loading();
+        } else if key.code == KeyCode::Esc && self.filter.is_some() {
+            self.clear_filter();
+        } else if key.code == KeyCode::Char('\'') && self.focus == Pane::List {
+            self.type_ahead = Some((String::new(), Instant::now()));
+        } else if key.code == KeyCode::Char('/') {
+            self.start_filter();
+        } else if key.code == KeyCode::Char('z') && self.focus == Pane::List {
+            self.compact = !self.compact;
+        } else if self.throttle(key.code) {
+            // Dropped, the title shows "…" for a moment.
+        } else if let SelectedLayout::Sitzungen = self.layout {
+            self.handle_sitzungen(key);
+        } else if let SelectedLayout::Tops = self.layout {
+            self.handle_tops(key);
+        } else {
+            self.handle_anträge(key);
+        }
+        if self.layout != layout {
+            self.clear_filter();
+            self.focus = Pane::List;
+        }
+        if !in_history && !self.current_view().same_place(&view) {
+            self.history.push(view);
+        }
+    }
+
+    fn current_view(&self) -> View {
+        let visit = |top: Option<session::VisitedTop>| session::Visit {
+            sitzung_id: self.sitzung.id,
+            sitzung: self.sitzung.name.clone(),
+            top,
+        };
+        let (visit, selected) = match self.layout {
+            SelectedLayout::Sitzungen => (None, self.sitzungen.selected_item().map(|s| s.id)),
+            SelectedLayout::Tops => (
+                Some(visit(None)),
+                self.tops_selected_sitzung.selected_item().map(|t| t.id),
+            ),
+            SelectedLayout::Anträge => (
+                Some(visit(Some(session::VisitedTop {
+                    id: self.top.id,
+                    name: self.top.name.clone(),
+                }))),
+                self.anträge_selected_top.selected_item().map(|a| a.id),
+            ),
+        };
+        View {
+            layout: self.layout.clone(),
+            visit,
+            selected,
+        }
+    }
+
+    /// Goes back, or forward with `forward`, through the visited views.
+    fn move_in_history(&mut self, forward: bool) {
+        let current = self.current_view();
+        let view = if forward {
+            self.history.forward(current)
+        } else {
+            self.history.back(current)
+        };
+        let Some(view) = view else {
+            self.set_status(if forward {
+                "Nothing to go forward to"
+            } else {
+                "Nothing to go back to"
+            });
+            return;
+        };
+        match view.visit {
+            Some(visit) => self.jump_to(visit),
+            None => self.layout = SelectedLayout::Sitzungen,
+        }
+        self.select_created = view.selected;
+        self.select_created_item();
+    }
+
+    fn handle_edit(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            Char('s') if ctrl => self.save_edit(),
+            Char('n') if ctrl => self.jump_to_invalid(true),
+            Char('p') if ctrl => self.jump_to_invalid(false),
+            Char('q') | Esc => self.leave_edit(),
+            Char('h') | Left => self.edit_buffer.unselect(),
+            Char('j') | Down => self.edit_buffer.next(),
+            Char('k') | Up => self.edit_buffer.previous(),
+            Char('e') => self.edit_value(),
+            Char('P') => self.preview_payload(),
+            Char('D') => self.show_diff = true,
+            Char('T') => self.reset_to_template(),
+            Char('a') if self.currently_creating == Some(SelectedLayout::Tops) => {
+                self.add_inline_antrag()
+            }
+            Char('x') if self.currently_creating == Some(SelectedLayout::Tops) => {
+                self.remove_inline_antrag()
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_text_area(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.update();
+                self.edit_param_pop = None;
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.current_text_area.select_all();
+                self.current_text_area.cut();
+            }
+            KeyCode::Char('a') if ctrl => self.current_text_area.move_cursor(CursorMove::Head),
+            KeyCode::Char('e') if ctrl => self.current_text_area.move_cursor(CursorMove::End),
+            KeyCode::F(11) => self.edit_fullscreen = !self.edit_fullscreen,
+            _ => {
+                self.current_text_area.input(key);
+            }
+        }
+    }
+
+    /// j/k step through the items without leaving focus mode.
+    fn handle_focus_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        let Some(scroll) = &mut self.focus_mode else {
+            return;
+        };
+        match key.code {
+            Char('F') | Char('q') | Esc => self.focus_mode = None,
+            PageDown => *scroll = scroll.saturating_add(10),
+            PageUp => *scroll = scroll.saturating_sub(10),
+            Char('j') | Down | Char('k') | Up => {
+                let down = matches!(key.code, Char('j') | Down);
+                self.focus_mode = Some(0);
+                match (&self.layout, down) {
+                    (SelectedLayout::Sitzungen, true) => self.sitzungen.next(),
+                    (SelectedLayout::Sitzungen, false) => self.sitzungen.previous(),
+                    (SelectedLayout::Tops, true) => self.tops_selected_sitzung.next(),
+                    (SelectedLayout::Tops, false) => self.tops_selected_sitzung.previous(),
+                    (SelectedLayout::Anträge, true) => self.anträge_selected_top.next(),
+                    (SelectedLayout::Anträge, false) => self.anträge_selected_top.previous(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_payload_preview(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('w') {
+            self.toggle_wrap();
+        } else {
+            self.payload_preview = None;
+        }
+    }
+
+    fn toggle_antrag_table(&mut self) {
+        self.session.antrag_table = !self.session.antrag_table;
+        if let Err(err) = self.session.save() {
+            self.set_status(format!("Could not save the table setting: {:#}", err));
+        }
+    }
+
+    fn toggle_wrap(&mut self) {
+        self.session.no_wrap = !self.session.no_wrap;
+        if let Err(err) = self.session.save() {
+            self.set_status(format!("Could not save the wrap setting: {:#}", err));
+        }
+    }
+
+    /// Wraps `paragraph` at word boundaries unless `w` turned that off.
+    fn soft_wrap<'t>(&self, paragraph: Paragraph<'t>) -> Paragraph<'t> {
+        if self.session.no_wrap {
+            paragraph
+        } else {
+            paragraph.wrap(Wrap { trim: false })
+        }
+    }
+
+    fn handle_sitzungen(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        if let Some((pending, _)) = self.pending_key.take() {
+            match (pending, key.code) {
+                ('d', Char('d')) => self.delete_sitzung(),
+                ('y', Char('y')) => self.yank_selected(),
+                _ => {}
+            }
+            return;
+        }
+        if self.handle_jump_key(key.code) {
+            return;
+        }
+        match key.code {
+            Char('q') | Esc => self.exit_app(),
+            Char('h') | Left => self.sitzungen.unselect(),
+            Char('j') | Down => {
+                self.sitzungen.next();
+                self.schedule_prefetch();
+            }
+            Char('k') | Up => {
+                self.sitzungen.previous();
+                self.schedule_prefetch();
+            }
+            Char('o') => self.open_sitzung(),
+            Char('e') => self.edit_sitzung(),
+            Char('p') => self.create_sitzung(),
+            Char('E') | F(2) => self.start_rename(),
+            Char('J') => self.open_raw_json(),
+            Char('d') => self.set_pending_key('d'),
+            Char('y') => self.set_pending_key('y'),
+            Char('i') => self.copy_id(),
+            Char('s') => self.cycle_sort_key(),
+            Char('S') => self.toggle_sort_order(),
+            Char('P') => self.toggle_past_sitzungen(),
+            Char('f') => self.toggle_pin(),
+            Char('L') => {
+                if let Some(sitzung) = self.sitzungen.selected_item() {
+                    self.copy_agenda_link(sitzung.id);
+                }
+            }
+            Char('a') => self.open_attendance(),
+            Char('D') => self.open_dashboard(),
+            Char('t') => self.open_trash(),
+            Char('T') => self.open_todays_sitzung(),
+            Char('M') => self.start_merge(),
+            _ => {}
+        }
+    }
+
+    fn handle_tops(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        if let Some((pending, _)) = self.pending_key.take() {
+            match (pending, key.code) {
+                ('d', Char('d')) => self.delete_top(),
+                ('y', Char('y')) => self.yank_selected(),
+                ('y', Char('m')) => self.copy_markdown(),
+                _ => {}
+            }
+            return;
+        }
+        if self.handle_jump_key(key.code) {
+            return;
+        }
+        match key.code {
+            Char('q') | Esc => self.switch_layout(SelectedLayout::Sitzungen),
+            Char('H') | Backspace => self.switch_layout(SelectedLayout::Sitzungen),
+            Char('h') | Left => self.tops_selected_sitzung.unselect(),
+            Char('j') | Down => self.tops_selected_sitzung.next(),
+            Char('k') | Up => self.tops_selected_sitzung.previous(),
+            Char('o') => self.open_top(),
+            Char('e') => self.edit_top(),
+            Char('E') | F(2) => self.start_rename(),
+            Char('J') => self.open_raw_json(),
+            Char('p') => self.create_top(),
+            Char('d') => self.set_pending_key('d'),
+            Char('y') => self.set_pending_key('y'),
+            Char('i') => self.copy_id(),
+            Char('[') => self.switch_sitzung(false),
+            Char(']') => self.switch_sitzung(true),
+            Char('n') => self.open_notes(),
+            Char('r') => self.open_protocol(),
+            Char('v') => self.open_agenda_summary(),
+            Char('L') => self.copy_agenda_link(self.sitzung.id),
+            Char('u') => self.toggle_open_tops_only(),
+            Char('P') => self.start_quick_create(),
+            Char('A') => self.author_search = Some(AuthorSearch::Prompt(Box::default())),
+            Char('W') => self.renumber_tops(),
+            Char('s') => self.cycle_sort_key(),
+            Char('S') => self.toggle_sort_order(),
+            Char('t') => self.open_trash(),
+            _ => {}
+        }
+    }
+
+    fn handle_anträge(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        if let Some((pending, _)) = self.pending_key.take() {
+            match (pending, key.code) {
+                ('d', Char('d')) => self.delete_antrag(),
+                ('y', Char('y')) => self.yank_selected(),
+                ('y', Char('m')) => self.copy_markdown(),
+                _ => {}
+            }
+            return;
+        }
+        if self.handle_jump_key(key.code) {
+            return;
+        }
+        if key.code == Tab {
+            self.focus = match self.focus {
+                Pane::List => Pane::Detail,
+                Pane::Detail => Pane::List,
+            };
+            return;
+        }
+        if self.focus == Pane::Detail {
+            match key.code {
+                Char('j') | Down => self.scroll_detail(1),
+                Char('k') | Up => self.scroll_detail(-1),
+                PageDown => self.scroll_detail(10),
+                PageUp => self.scroll_detail(-10),
+                Char('z') => self.toggle_section(),
+                Char('Z') => self.collapsed.toggle_all(),
+                Char('w') => self.toggle_wrap(),
+                Char('q') | Esc => self.focus = Pane::List,
+                _ => {}
+            }
+            return;
+        }
+        match key.code {
+            Char('q') | Esc => self.switch_layout(SelectedLayout::Tops),
+            Char('H') | Backspace => self.switch_layout(SelectedLayout::Sitzungen),
+            Char('h') | Left => self.anträge_selected_top.unselect(),
+            Char('j') | Down => {
+                self.anträge_selected_top.next();
+                self.detail_scroll = 0;
+            }
+            Char('k') | Up => {
+                self.anträge_selected_top.previous();
+                self.detail_scroll = 0;
+            }
+            Char('e') => self.edit_antag(),
+            Char('E') | F(2) => self.start_rename(),
+            Char('J') => self.open_raw_json(),
+            Char('p') => self.create_antrag(),
+            Char('P') => self.start_quick_create(),
+            Char('c') => self.duplicate_antrag(),
+            Char('d') => self.set_pending_key('d'),
+            Char('y') => self.set_pending_key('y'),
+            Char('i') => self.copy_id(),
+            Char('v') => self.cycle_antrag_status(),
+            Char('a') if !self.read_only() => self.record_vote(AntragStatus::Angenommen),
+            Char('x') if !self.read_only() => self.record_vote(AntragStatus::Abgelehnt),
+            Char('[') => self.switch_top(false),
+            Char(']') => self.switch_top(true),
+            Char('O') => self.select_anhang(),
+            Char('I') => self.start_import(),
+            Char('X') => self.export_pdf(),
+            Char('T') => self.toggle_antrag_table(),
+            Char('g') => self.locate_parents(),
+            Char('s') => self.cycle_sort_key(),
+            Char('S') => self.toggle_sort_order(),
+            Char('t') => self.open_trash(),
+            _ => {}
+        }
+    }
+
+    /// Makes `self.top` and `self.sitzung` the parents of the selected
+    /// Antrag, fetching them if the Antrag names a different Top, and shows
+    /// where it belongs.
+    fn locate_parents(&mut self) {
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            self.set_status("No Antrag selected");
+            return;
+        };
+        match antrag.top_id {
+            Some(top_id) if top_id != self.top.id => {
+                self.locating = Some(top_id);
+                self.backend.send(Request::Top(top_id));
+                self.set_status("Looking up the Top…");
+            }
+            _ => self.located_top(self.top.clone()),
+        }
+    }
+
+    fn located_top(&mut self, top: Top) {
+        self.locating = None;
+        let sitzung_id = top.sitzung_id;
+        self.top = top;
+        match sitzung_id {
+            Some(id) if id != self.sitzung.id => {
+                if let Some(sitzung) = self.all_sitzungen.iter().find(|s| s.id == id) {
+                    self.sitzung = sitzung.clone();
+                } else {
+                    self.locating = Some(id);
+                    self.backend.send(Request::Sitzung(id));
+                    self.set_status("Looking up the Sitzung…");
+                    return;
+                }
+            }
+            _ => {}
+        }
+        self.show_breadcrumb();
+    }
+
+    fn show_breadcrumb(&mut self) {
+        let titel = self
+            .anträge_selected_top
+            .selected_item()
+            .map(|antrag| antrag.titel.as_str())
+            .unwrap_or_default();
+        let crumbs = breadcrumb(&self.sitzung, &self.top, titel);
+        self.set_status(crumbs);
+    }
+
+    /// Drops keys that load from the backend if they come faster than the
+    /// configured interval, so held or mashed keys can't flood it.
+    fn throttle(&mut self, code: KeyCode) -> bool {
+        if self.focus == Pane::Detail || !fetches(&self.layout, code) {
+            return false;
+        }
+        let interval = self.config.request_interval();
+        if self.last_request.is_some_and(|at| at.elapsed() < interval) {
+            self.throttled = Some(Instant::now());
+            return true;
+        }
+        self.last_request = Some(Instant::now());
+        false
+    }
+
+    /// Also forgets an item waiting to be selected, it belongs to the list
+    /// that is left.
+    fn switch_layout(&mut self, layout: SelectedLayout) {
+        self.layout = layout.clone();
+        self.select_created = None;
+        self.edit_created = false;
+    }
+
+    /// Keeps the editor open while a required field is empty.
+    fn refuse_incomplete(&mut self) -> bool {
+        let Some(layout) = self
+            .currently_editing
+            .as_ref()
+            .or(self.currently_creating.as_ref())
+        else {
+            return false;
+        };
+        let missing = missing_required(layout, &self.edit_buffer.items);
+        if missing.is_empty() {
+            return false;
+        }
+        let message = format!("Required: {} (Ctrl+N/P to jump)", missing.join(", "));
+        let first = next_invalid(layout, &self.edit_buffer.items, None, true);
+        self.edit_buffer.state.select(first);
+        self.confirm_leave = false;
+        self.saving = None;
+        self.quit_after_save = false;
+        self.set_status(message);
+        true
+    }
+
+    /// Selects the next or previous empty required field or unreadable date.
+    fn jump_to_invalid(&mut self, forward: bool) {
+        let Some(layout) = self
+            .currently_editing
+            .as_ref()
+            .or(self.currently_creating.as_ref())
+        else {
+            return;
+        };
+        let from = self.edit_buffer.state.selected();
+        match next_invalid(layout, &self.edit_buffer.items, from, forward) {
+            Some(index) => self.edit_buffer.state.select(Some(index)),
+            None => self.set_status("All fields are filled in"),
+        }
+    }
+
+    /// Saves and leaves the editor once the backend took the save.
+    fn exit_edit(&mut self) {
+        if self.leave_after_save || self.refuse_incomplete() || self.hold_stale_save(true) {
+            return;
+        }
+        if self.read_only() {
+            self.set_status("Read-only, log in to save");
+            return;
+        }
+        if self.currently_editing.is_some() {
+            self.patch();
+        }
+        if self.currently_creating.is_some() {
+            self.put();
+        }
+        self.leave_after_save = true;
+        self.confirm_leave = false;
+        self.set_status("Saving…");
+    }
+
+    /// Leaves the editor if it was waiting for the save that went through.
+    fn close_after_save(&mut self) {
+        if std::mem::take(&mut self.leave_after_save) {
+            self.close_editor();
+        }
+    }
+
+    fn close_editor(&mut self) {
+        self.leave_after_save = false;
+        self.currently_editing = None;
+        self.currently_creating = None;
+        self.confirm_leave = false;
+        self.edit_version = None;
+        self.edit_fetched = None;
+        self.edit_original = vec![];
+        self.edit_buffer = StatefulList::with_items(vec![]);
+    }
+
+    /// Saves without leaving the editor. A new item can only be created once,
+    /// so saving while creating also closes the editor.
+    fn save_edit(&mut self) {
+        if self.currently_creating.is_some() {
+            self.exit_edit();
+            return;
+        }
+        if self.leave_after_save || self.refuse_incomplete() || self.hold_stale_save(false) {
+            return;
+        }
+        self.patch();
+        self.set_status("Saving…");
+    }
+
+    fn has_unsaved_changes(&self) -> bool {
+        if self.currently_creating.is_some() {
+            return self.edit_buffer.items.iter().any(|p| !p.text.is_empty());
+        }
+        let fields = |params: &[Param]| {
+            params
+                .iter()
+                .map(|p| (p.key.clone(), p.text.clone()))
+                .collect::<Vec<_>>()
+        };
+        fields(&self.edit_buffer.items) != fields(&self.edit_original)
+    }
+
+    fn leave_edit(&mut self) {
+        if self.has_unsaved_changes() {
+            self.confirm_leave = true;
+        } else {
+            self.close_editor();
+        }
+    }
+
+    fn handle_leave_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => self.exit_edit(),
+            KeyCode::Char('n') => self.close_editor(),
+            KeyCode::Esc => self.confirm_leave = false,
+            _ => {}
+        }
+    }
+}
+
+impl Widget for &mut App<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            render_too_small(area, buf, self.config.lang);
+            return;
+        }
+        if let Some(scroll) = self.focus_mode {
+            self.render_focus(area, buf, scroll);
+            return;
+        }
+        let bar = if self.compact { 1 } else { 2 };
+        let vertical = Layout::vertical([
+            Constraint::Length(bar),
+            Constraint::Min(0),
+            Constraint::Length(bar),
+        ]);
+        let [header_area, rest_area, footer_area] = vertical.areas(area);
+        // Hints and prompts use the last footer line, the status the first.
+        let hint_area = Rect {
+            y: footer_area.bottom().saturating_sub(1),
+            height: footer_area.height.min(1),
+            ..footer_area
+        };
+
+        let profile = match &self.profile {
+            _ if self.config.profiles.is_empty() => None,
+            Some(profile) => Some(profile.as_str()),
+            None => Some("default"),
+        };
+        let lang = self.config.lang;
+        let session = self
+            .token_expires
+            .filter(|_| !self.read_only())
+            .map(|expires| session_span(expires.saturating_duration_since(Instant::now()), lang));
+        let title = match &self.user {
+            _ if self.read_only() => format!("{} ({})", APP_TITLE, tr(lang, "read-only")),
+            Some(user) => format!("{} · {}", APP_TITLE, user),
+            None => APP_TITLE.to_string(),
+        };
+        render_title(
+            header_area,
+            buf,
+            &title,
+            self.insecure,
+            profile,
+            session,
+            self.version_warning.as_deref(),
+        );
+        if self.edit_param_pop.is_some() {
+            self.render_edit_param(rest_area, buf);
+        } else if self.currently_editing.is_some() || self.currently_creating.is_some() {
+            self.render_edit(rest_area, buf);
+            if self.payload_preview.is_some() {
+                self.render_payload_preview(rest_area, buf);
+            }
+            if self.show_diff {
+                let lines = diff_lines(&self.edit_original, &self.edit_buffer.items);
+                self.render_text_popup(rest_area, buf, "Changes", lines);
+            }
+            if self.confirm_leave {
+                self.render_text_popup(
+                    rest_area,
+                    buf,
+                    "Unsaved changes",
+                    tr(
+                        self.config.lang,
+                        "Save before leaving?\n\ny/Enter to save, n to discard, Esc to keep editing",
+                    ),
+                );
+            }
+        } else if self.dashboard.is_some() {
+            self.render_dashboard(rest_area, buf);
+        } else {
+            self.render_overview(rest_area, buf);
+            if self.verbose {
+                self.render_debug_overlay(rest_area, buf);
+            }
+            if self.show_log {
+                self.render_action_log(rest_area, buf);
+            }
+            if let Some(step) = &self.import {
+                self.render_import(rest_area, buf, step);
+            }
+            if self.merge.is_some() {
+                self.render_merge(rest_area, buf);
+            }
+            if self.trash.is_some() {
+                self.render_trash(rest_area, buf);
+            }
+            if let Some(raw) = &self.raw_json {
+                self.render_raw_json(rest_area, buf, raw);
+            }
+            if let Some(protocol) = &self.protocol {
+                self.render_protocol(rest_area, buf, protocol);
+            }
+            if let Some(summary) = &self.agenda_summary {
+                self.render_agenda_summary(rest_area, buf, summary);
+            }
+            if self.notes.is_some() {
+                self.render_notes(rest_area, buf);
+            }
+            if let Some(switcher) = &mut self.switcher {
+                let items = switcher
+                    .items
+                    .iter()
+                    .map(|visit| ListItem::new(visit.label()))
+                    .collect();
+                let theme = &self.config.theme;
+                let title = tr(self.config.lang, "Recent");
+                render_list_popup(rest_area, buf, theme, title, items, &mut switcher.state);
+            }
+            if let Some(switcher) = &mut self.profile_switcher {
+                let items = switcher
+                    .items
+                    .iter()
+                    .map(|profile| ListItem::new(profile.as_deref().unwrap_or("default")))
+                    .collect();
+                let theme = &self.config.theme;
+                render_list_popup(
+                    rest_area,
+                    buf,
+                    theme,
+                    tr(self.config.lang, "Profiles"),
+                    items,
+                    &mut switcher.state,
+                );
+            }
+            if let Some(picker) = &mut self.anhang_picker {
+                let items = picker
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, anhang)| ListItem::new(format!("{}. {}", i + 1, anhang)))
+                    .collect();
+                let theme = &self.config.theme;
+                render_list_popup(rest_area, buf, theme, "Anhänge", items, &mut picker.state);
+            }
+            match &mut self.author_search {
+                Some(AuthorSearch::Searching(author)) => {
+                    let template = tr(self.config.lang, "Looking through all Tops for \"{}\"…");
+                    let text = fill(template, &[author]);
+                    self.render_text_popup(rest_area, buf, "Anträge by author", text);
+                }
+                Some(AuthorSearch::Results(author, results)) => {
+                    let items = results
+                        .items
+                        .iter()
+                        .map(|(top, antrag)| {
+                            ListItem::new(Line::from(vec![
+                                Span::raw(antrag.titel.clone()),
+                                format!(" — {}", top.name).dim(),
+                            ]))
+                        })
+                        .collect();
+                    let title = tr(self.config.lang, "Anträge by \"{}\"").replace("{}", author);
+                    let theme = &self.config.theme;
+                    render_list_popup(rest_area, buf, theme, &title, items, &mut results.state);
+                }
+                _ => {}
+            }
+            if let Some(attendance) = &mut self.attendance {
+                let members = &attendance.members.items;
+                let present = members.iter().filter(|(_, present)| *present).count();
+                let title = format!(
+                    "{} — {}/{} {}",
+                    tr(self.config.lang, "Attendance"),
+                    present,
+                    members.len(),
+                    tr(self.config.lang, "present")
+                );
+                let items = members
+                    .iter()
+                    .map(|(name, present)| {
+                        ListItem::new(format!("[{}] {}", if *present { "x" } else { " " }, name))
+                    })
+                    .collect();
+                let theme = &self.config.theme;
+                let state = &mut attendance.members.state;
+                render_list_popup(rest_area, buf, theme, &title, items, state);
+            }
+        }
+        if self.confirm_quit {
+            self.render_text_popup(
+                rest_area,
+                buf,
+                "Quit",
+                tr(
+                    self.config.lang,
+                    "You have unsaved edits — save / discard / cancel?\n\n\
+                     s/Enter to save them and quit, d to quit without them, Esc to go back",
+                ),
+            );
+        }
+        if self.conflict {
+            self.render_text_popup(
+                rest_area,
+                buf,
+                "Conflict",
+                tr(
+                    self.config.lang,
+                    "This item changed on the server — reload and reapply?\n\n\
+                     y/Enter to fetch it again and see your changes on top of it, n/Esc to dismiss",
+                ),
+            );
+        }
+        if let Some(fetched) = self.edit_fetched.filter(|_| self.stale_save.is_some()) {
+            let template = tr(
+                self.config.lang,
+                "This item was fetched {} minutes ago and may have changed since.\n\n\
+                 y/Enter to fetch it again and see your changes on top of it, \
+                 n to save over it anyway, Esc to go back",
+            );
+            let text = fill(template, &[&(fetched.elapsed().as_secs() / 60)]);
+            self.render_text_popup(rest_area, buf, "Stale copy", text);
+        }
+        if let Some((digits, _)) = &self.pending_jump {
+            render_jump_prompt(hint_area, buf, digits);
+        } else if let Some((typed, _)) = &self.type_ahead {
+            render_prompt(hint_area, buf, tr(self.config.lang, "Type-ahead"), typed);
+        } else if let Some(filter) = self.filter.as_ref().filter(|filter| filter.typing) {
+            render_prompt(
+                hint_area,
+                buf,
+                tr(self.config.lang, "Filter"),
+                &filter.query(),
+            );
+        } else if let Some(AuthorSearch::Prompt(input)) = &self.author_search {
+            let label = tr(self.config.lang, "Author");
+            render_prompt(hint_area, buf, label, &input.lines().join(" "));
+        } else if let Some(input) = &self.quick_create {
+            let label = match self.layout {
+                SelectedLayout::Tops => "New Top",
+                _ => "New Antrag",
+            };
+            let label = tr(self.config.lang, label);
+            render_prompt(hint_area, buf, label, &input.lines().join(" "));
+        } else {
+            let hints = self.footer_hints();
+            render_footer(hint_area, buf, &hints, &self.keymap, self.config.lang);
+        }
+        if let Some((message, _)) = &self.status {
+            let color = self.status_color.unwrap_or(self.config.theme.selected_fg);
+            let message = tr_message(self.config.lang, message);
+            render_status(footer_area, buf, &message, color);
+        }
+    }
+}
+
+impl App<'_> {
+    fn render_overview(&mut self, area: Rect, buf: &mut Buffer) {
+        let lang = self.config.lang;
+        let title = match self.layout {
+            SelectedLayout::Sitzungen => {
+                let now = Local::now().naive_local();
+                let past = self.all_sitzungen.iter().filter(|s| s.datum < now).count();
+                let upcoming = self.all_sitzungen.len() - past;
+                let template = if self.show_past {
+                    "Sitzungen — {} pinned, {} upcoming, {} past"
+                } else {
+                    "Sitzungen — {} pinned, {} upcoming, {} past, hidden"
+                };
+                let pinned = self
+                    .all_sitzungen
+                    .iter()
+                    .filter(|s| self.session.pinned.contains(&s.id))
+                    .count();
+                fill(tr(lang, template), &[&pinned, &upcoming, &past])
+            }
+            SelectedLayout::Tops if self.open_tops_only => {
+                let template = tr(lang, "Tops — {} — with open Anträge");
+                fill(template, &[&self.sitzung.name])
+            }
+            SelectedLayout::Tops => format!("Tops — {}", self.sitzung.name),
+            SelectedLayout::Anträge => {
+                format!("Anträge — {} › {}", self.sitzung.name, self.top.name)
+            }
+        };
+        let loading = match self.layout {
+            SelectedLayout::Sitzungen => self.awaiting_sitzungen,
+            SelectedLayout::Tops => self.awaiting_tops.is_some(),
+            SelectedLayout::Anträge => self.awaiting_anträge.is_some(),
+        };
+        let loading = match &self.loading {
+            Some((since, _)) if loading => loading_label(since.elapsed(), lang),
+            _ => String::new(),
+        };
+        let filtered = match &self.filter {
+            Some(filter) if filter.layout() == self.layout && !filter.query().is_empty() => {
+                fill(tr(lang, " — filter \"{}\""), &[&filter.query()])
+            }
+            _ => String::new(),
+        };
+        let throttled = self
+            .throttled
+            .is_some_and(|at| at.elapsed() < self.config.request_interval());
+        let title = format!(
+            "{} ({}){}{}{}",
+            title,
+            self.sort().label(),
+            filtered,
+            loading,
+            if throttled { " …" } else { "" }
+        );
+        let title = fit_to_width(&title, area.width as usize, false).remove(0);
+        let outer_block = Block::default()
+            .borders(Borders::NONE)
+            .fg(self.config.theme.text)
+            .bg(self.config.theme.header_bg)
+            .title(title)
+            .title_alignment(Alignment::Center);
+        let inner_block = Block::default()
+            .borders(Borders::NONE)
+            .fg(self.config.theme.text)
+            .bg(self.config.theme.row_bg);
+
+        let outer_area = area;
+        let mut inner_area = outer_block.inner(outer_area);
+
+        outer_block.render(outer_area, buf);
+
+        if let SelectedLayout::Sitzungen = self.layout {
+            self.render_sitzungen_table(inner_area, inner_block, buf);
+            return;
+        }
+
+        if let Some((current, total)) = self.agenda_progress() {
+            let [gauge_area, rest_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner_area);
+            inner_area = rest_area;
+            Gauge::default()
+                .gauge_style(
+                    Style::default()
+                        .fg(self.config.theme.selected_fg)
+                        .bg(self.config.theme.row_bg),
+                )
+                .ratio(current as f64 / total as f64)
+                .label(agenda_label(current, total, self.config.lang))
+                .render(gauge_area, buf);
+        }
+
+        if let SelectedLayout::Anträge = self.layout {
+            let [list_area, detail_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(inner_area);
+            inner_area = list_area;
+            self.render_antrag_detail(detail_area, buf);
+            if self.session.antrag_table {
+                self.render_anträge_table(inner_area, inner_block, buf);
+                return;
+            }
+        }
+
+        // The highlight symbol and the scrollbar take one column each.
+        let width = (inner_area.width as usize).saturating_sub(2);
+        let wrap = self.config.wrap_names && !self.compact;
+        let listelement: Vec<ListItem> = if let SelectedLayout::Tops = self.layout {
+            self.tops_selected_sitzung
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let number = format!("{}. ", i + 1);
+                    let count = match self.antrag_counts.get(&t.id) {
+                        Some(Some(count)) if count.open > 0 => {
+                            format!(" ({}, {} open)", count.total, count.open)
+                        }
+                        Some(Some(count)) => format!(" ({})", count.total),
+                        _ => String::new(),
+                    };
+                    let presenter = match t.verantwortlich.as_deref() {
+                        Some(name) if !name.is_empty() => format!(" — {}", name),
+                        _ => String::new(),
+                    };
+                    let name_width =
+                        width.saturating_sub(number.width() + presenter.width() + count.width());
+                    let name = fit_to_width(&t.name, name_width, wrap);
+                    let mut lines = emphasize_matches(name, &self.filter_matches(&t.name));
+                    let indent = " ".repeat(number.width());
+                    for (i, line) in lines.iter_mut().enumerate() {
+                        let prefix = if i == 0 {
+                            number.clone()
+                        } else {
+                            indent.clone()
+                        };
+                        line.spans.insert(0, Span::raw(prefix));
+                    }
+                    let last = lines.last_mut().unwrap();
+                    last.spans.push(presenter.dim());
+                    last.spans.push(Span::raw(count));
+                    ListItem::new(lines)
+                })
+                .collect()
+        } else {
+            self.anträge_selected_top
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, a)| {
+                    let matched = self.filter_matches(&a.titel);
+                    let typ_colors = &self.config.typ_colors;
+                    antrag_list_item(i + 1, a, width, wrap, &matched, typ_colors)
+                })
+                .collect()
+        };
+        let heights: Vec<u16> = listelement
+            .iter()
+            .map(|item| item.height() as u16)
+            .collect();
+        let items = List::new(listelement)
+            .block(inner_block)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.config.theme.selected_fg),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        if let SelectedLayout::Tops = self.layout {
+            StatefulWidget::render(
+                items,
+                inner_area,
+                buf,
+                &mut self.tops_selected_sitzung.state,
+            );
+            let state = &self.tops_selected_sitzung.state;
+            let (len, position) = scroll_rows(&heights, state.offset());
+            render_scrollbar(inner_area, buf, len, position);
+            self.render_rename(inner_area, buf, 1, &heights, state);
+        } else {
+            StatefulWidget::render(items, inner_area, buf, &mut self.anträge_selected_top.state);
+            if self.anträge_selected_top.items.is_empty() && self.awaiting_anträge.is_none() {
+                let placeholder = match &self.filter {
+                    Some(filter) if !filter.query().is_empty() => {
+                        tr(self.config.lang, "No matches")
+                    }
+                    _ => tr(self.config.lang, "No Anträge for this Top"),
+                };
+                Paragraph::new(placeholder.dim())
+                    .alignment(Alignment::Center)
+                    .render(inner_area, buf);
+            }
+            let state = &self.anträge_selected_top.state;
+            let (len, position) = scroll_rows(&heights, state.offset());
+            render_scrollbar(inner_area, buf, len, position);
+            self.render_rename(inner_area, buf, 1, &heights, state);
+        }
+    }
+
+    /// Draws the rename input over the first line of the selected row,
+    /// starting `skip` columns in.
+    fn render_rename(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        skip: u16,
+        heights: &[u16],
+        state: &ListState,
+    ) {
+        let Some(input) = &self.rename else {
+            return;
+        };
+        let Some(selected) = state.selected() else {
+            return;
+        };
+        if let Some(row) = row_area(area, heights, state.offset(), selected) {
+            let row = Rect {
+                x: row.x + skip,
+                width: row.width.saturating_sub(skip + 1),
+                ..row
+            };
+            Clear.render(row, buf);
+            input.widget().render(row, buf);
+        }
+    }
+
+    fn render_sitzungen_table(&mut self, area: Rect, block: Block, buf: &mut Buffer) {
+        let now = Local::now().naive_local();
+        let inner_area = block.inner(area);
+        // Highlight symbol, number and date columns, column spacing and the
+        // scrollbar.
+        let name_width = (area.width as usize).saturating_sub(1 + 5 + 10 + 2 * 2 + 1);
+        let pinned = &self.session.pinned;
+        let last_pinned = self
+            .sitzungen
+            .items
+            .iter()
+            .rposition(|s| pinned.contains(&s.id));
+        let wrap = self.config.wrap_names && !self.compact;
+        let mut heights = vec![];
+        let rows: Vec<Row> = self
+            .sitzungen
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let name = fit_to_width(&s.name, name_width, wrap);
+                let height = name.len() as u16;
+                heights.push(height);
+                let name = emphasize_matches(name, &self.filter_matches(&s.name));
+                let marker = if pinned.contains(&s.id) { "★" } else { " " };
+                let row = Row::new(vec![
+                    Cell::from(format!("{}{}.", marker, i + 1)),
+                    Cell::from(s.datum.format("%Y-%m-%d").to_string()),
+                    Cell::from(Text::from(name)),
+                ])
+                .height(height);
+                let mut style = Style::default();
+                if s.datum < now {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                // Underlining the last pinned row separates the pinned section.
+                if Some(i) == last_pinned {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                row.style(style)
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(10),
+                Constraint::Min(0),
+            ],
+        )
+        .block(block)
+        .column_spacing(2)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED)
+                .fg(self.config.theme.selected_fg),
+        )
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+
+        let mut state = TableState::default()
+            .with_selected(self.sitzungen.state.selected())
+            .with_offset(self.sitzungen.state.offset());
+        StatefulWidget::render(table, area, buf, &mut state);
+        *self.sitzungen.state.offset_mut() = state.offset();
+        if self.sitzungen.items.is_empty() && !self.awaiting_sitzungen {
+            let placeholder = match &self.filter {
+                Some(filter) if !filter.query().is_empty() => tr(self.config.lang, "No matches"),
+                _ if self.show_past => tr(self.config.lang, "No Sitzungen"),
+                _ => tr(self.config.lang, "No upcoming Sitzungen"),
+            };
+            Paragraph::new(placeholder.dim())
+                .alignment(Alignment::Center)
+                .render(inner_area, buf);
+        }
+        // Past the highlight symbol, the number and date columns and their
+        // spacing.
+        self.render_rename(
+            area,
+            buf,
+            1 + 5 + 2 + 10 + 2,
+            &heights,
+            &self.sitzungen.state,
+        );
+        let (len, position) = scroll_rows(&heights, self.sitzungen.state.offset());
+        render_scrollbar(area, buf, len, position);
+    }
+
+    fn render_anträge_table(&mut self, area: Rect, block: Block, buf: &mut Buffer) {
+        let anträge = &self.anträge_selected_top.items;
+        let columns = antrag_columns(&self.config.antrag_table, anträge);
+        // Highlight symbol, number column, the spacing between all columns
+        // and the scrollbar.
+        let fixed: usize = columns
+            .iter()
+            .map(|(_, width)| width.unwrap_or(0) as usize)
+            .sum();
+        let chrome = 1 + 4 + 2 * columns.len() + 1;
+        let titel_width = match columns[0] {
+            (_, Some(width)) => width as usize,
+            (_, None) => (area.width as usize).saturating_sub(fixed + chrome),
+        };
+        let wrap = self.config.wrap_names && !self.compact;
+        let mut heights = vec![];
+        let rows: Vec<Row> = anträge
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let titel = fit_to_width(&a.titel, titel_width, wrap);
+                let height = titel.len() as u16;
+                heights.push(height);
+                let mut titel = Some(emphasize_matches(titel, &self.filter_matches(&a.titel)));
+                let mut cells = vec![Cell::from(format!("{}.", i + 1))];
+                for (column, _) in &columns {
+                    cells.push(match column {
+                        AntragColumn::Titel => {
+                            Cell::from(Text::from(titel.take().unwrap_or_default()))
+                        }
+                        AntragColumn::Antragssteller => {
+                            Cell::from(a.author.clone().unwrap_or_default())
+                        }
+                        AntragColumn::Status => Cell::from(Span::styled(
+                            format!(" {} ", a.status.label()),
+                            Style::default()
+                                .fg(Color::Black)
+                                .bg(a.status.color())
+                                .add_modifier(Modifier::BOLD),
+                        )),
+                        AntragColumn::Erstellt => Cell::from(
+                            a.created_at
+                                .as_deref()
+                                .map(|at| format_timestamp(at, &Local))
+                                .and_then(|at| at.get(..10).map(str::to_string))
+                                .unwrap_or_default(),
+                        ),
+                    });
+                }
+                Row::new(cells).height(height)
+            })
+            .collect();
+        let mut widths = vec![Constraint::Length(4)];
+        widths.extend(columns.iter().map(|(_, width)| match width {
+            Some(width) => Constraint::Length(*width),
+            None => Constraint::Min(0),
+        }));
+        let header =
+            Row::new(std::iter::once("#").chain(columns.iter().map(|(column, _)| column.label())))
+                .style(Style::default().add_modifier(Modifier::BOLD));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block)
+            .column_spacing(2)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(self.config.theme.selected_fg),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        let mut state = TableState::default()
+            .with_selected(self.anträge_selected_top.state.selected())
+            .with_offset(self.anträge_selected_top.state.offset());
+        StatefulWidget::render(table, area, buf, &mut state);
+        *self.anträge_selected_top.state.offset_mut() = state.offset();
+        // Below the header row.
+        let body = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        if self.anträge_selected_top.items.is_empty() && self.awaiting_anträge.is_none() {
+            let placeholder = match &self.filter {
+                Some(filter) if !filter.query().is_empty() => tr(self.config.lang, "No matches"),
+                _ => tr(self.config.lang, "No Anträge for this Top"),
+            };
+            Paragraph::new(placeholder.dim())
+                .alignment(Alignment::Center)
+                .render(body, buf);
+        }
+        // Past the highlight symbol, the number column and its spacing, the
+        // Titel always comes next.
+        let state = &self.anträge_selected_top.state;
+        self.render_rename(body, buf, 1 + 4 + 2, &heights, state);
+        let (len, position) = scroll_rows(&heights, state.offset());
+        render_scrollbar(body, buf, len, position);
+    }
+
+    fn scroll_detail(&mut self, by: i32) {
+        let lines = self
+            .anträge_selected_top
+            .selected_item()
+            .map_or(0, |antrag| {
+                antrag_detail_lines(antrag, &self.collapsed).0.len()
+            });
+        let max = lines.saturating_sub(1) as i32;
+        self.detail_scroll = (self.detail_scroll as i32 + by).clamp(0, max) as u16;
+    }
+
+    /// Folds or unfolds the section at the top of the details and keeps its
+    /// heading in view.
+    fn toggle_section(&mut self) {
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            return;
+        };
+        let (_, headings) = antrag_detail_lines(antrag, &self.collapsed);
+        let Some(section) = section_at(&headings, self.detail_scroll as usize) else {
+            return;
+        };
+        let collapsed = self.collapsed.get_mut(section);
+        *collapsed = !*collapsed;
+        if let Some((line, _)) = headings.iter().find(|(_, s)| *s == section) {
+            self.detail_scroll = *line as u16;
+        }
+    }
+
+    fn render_antrag_detail(&self, area: Rect, buf: &mut Buffer) {
+        let focused = self.focus == Pane::Detail;
+        let border = if focused {
+            Style::default().fg(self.config.theme.selected_fg)
+        } else {
+            Style::default()
+        };
+        let block = Block::default()
+            .borders(Borders::LEFT)
+            .border_type(if focused {
+                BorderType::Thick
+            } else {
+                BorderType::Plain
+            })
+            .border_style(border)
+            .fg(self.config.theme.text)
+            .bg(self.config.theme.row_bg)
+            .title(Span::styled(tr(self.config.lang, "Details"), border));
+        let Some(antrag) = self.anträge_selected_top.selected_item() else {
+            block.render(area, buf);
+            return;
+        };
+
+        let (mut lines, headings) = antrag_detail_lines(antrag, &self.collapsed);
+        if focused {
+            let current = section_at(&headings, self.detail_scroll as usize);
+            if let Some((line, _)) = headings.iter().find(|(_, s)| Some(*s) == current) {
+                lines[*line] = lines[*line]
+                    .clone()
+                    .patch_style(Style::default().reversed());
+            }
+        }
+        let line_count = lines.len();
+        self.soft_wrap(Paragraph::new(lines))
+            .block(block)
+            .scroll((self.detail_scroll, 0))
+            .render(area, buf);
+        render_scrollbar(area, buf, line_count, self.detail_scroll as usize);
+    }
+
+    fn render_edit(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(editing) = &self.currently_editing {
+            let title = match editing {
+                SelectedLayout::Sitzungen => "Edit Sitzung",
+                SelectedLayout::Tops => "Edit Top",
+                SelectedLayout::Anträge => "Edit Antrag",
+            };
+            let title = tr(self.config.lang, title);
+            let outer_block = Block::default()
+                .borders(Borders::NONE)
+                .fg(self.config.theme.text)
+                .bg(self.config.theme.header_bg)
+                .title(title)
+                .title_alignment(Alignment::Center);
+            let inner_block = Block::default()
+                .borders(Borders::NONE)
+                .fg(self.config.theme.text)
+                .bg(self.config.theme.row_bg);
+
+            let outer_area = area;
+            let inner_area = outer_block.inner(outer_area);
+
+            outer_block.render(outer_area, buf);
+
+            let listelement: Vec<ListItem> = self
+                .edit_buffer
+                .items
+                .iter()
+                .map(|p| param_item(editing, p))
+                .collect();
+
+            let items = List::new(listelement)
+                .block(inner_block)
+                .highlight_style(
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .add_modifier(Modifier::REVERSED)
+                        .fg(self.config.theme.selected_fg),
+                )
+                .highlight_symbol(">")
+                .highlight_spacing(HighlightSpacing::Always);
+            StatefulWidget::render(items, inner_area, buf, &mut self.edit_buffer.state);
+        }
+
+        if let Some(editing) = &self.currently_creating {
+            let title = match editing {
+                SelectedLayout::Sitzungen => "Create Sitzung",
+                SelectedLayout::Tops => "Create Top",
+                SelectedLayout::Anträge => "Create Antrag",
+            };
+            let title = tr(self.config.lang, title);
+            let outer_block = Block::default()
+                .borders(Borders::NONE)
+                .fg(self.config.theme.text)
+                .bg(self.config.theme.header_bg)
+                .title(title)
+                .title_alignment(Alignment::Center);
+            let inner_block = Block::default()
+                .borders(Borders::NONE)
+                .fg(self.config.theme.text)
+                .bg(self.config.theme.row_bg);
+
+            let outer_area = area;
+            let inner_area = outer_block.inner(outer_area);
+
+            outer_block.render(outer_area, buf);
+
+            let listelement: Vec<ListItem> = self
+                .edit_buffer
+                .items
+                .iter()
+                .map(|p| param_item(editing, p))
+                .collect();
+
+            let items = List::new(listelement)
+                .block(inner_block)
+                .highlight_style(
+                    Style::default()
+                        .add_modifier(Modifier::BOLD)
+                        .add_modifier(Modifier::REVERSED)
+                        .fg(self.config.theme.selected_fg),
+                )
+                .highlight_symbol(">")
+                .highlight_spacing(HighlightSpacing::Always);
+            StatefulWidget::render(items, inner_area, buf, &mut self.edit_buffer.state);
+        }
+    }
+
+    fn render_notes(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(notes) = &mut self.notes else {
+            return;
+        };
+        let name = self
+            .tops_selected_sitzung
+            .items
+            .iter()
+            .find(|t| t.id == notes.top)
+            .map_or("", |t| t.name.as_str());
+        let theme = &self.config.theme;
+        let popup_layout = centered_rect(80, 80, area);
+        let popup = Block::default()
+            .title(format!("Protokoll — {} — {}", name, notes.state()))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.text))
+            .title_style(Style::default().fg(theme.text))
+            .style(Style::default().bg(theme.row_bg).fg(theme.text));
+        Clear.render(popup_layout, buf);
+        notes.input.set_block(popup);
+        notes.input.widget().render(popup_layout, buf);
+    }
+
+    fn render_edit_param(&mut self, area: Rect, buf: &mut Buffer) {
+        let size = if self.edit_fullscreen { 96 } else { 50 };
+        let popup_layout = centered_rect(size, size, area);
+        let popup = Block::default()
+            .title(tr(self.config.lang, "Edit Value"))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        let param = self.edit_param_pop.as_ref().unwrap();
+        let mut tile = param.titel.clone();
+        if param.key == "datum" {
+            let input = self.current_text_area.lines().join(" ");
+            match datum::parse(&input, Local::now().naive_local()) {
+                Some(datum) => tile = format!("{} → {}", param.titel, datum),
+                None => tile = format!("{} → ?", param.titel),
+            }
+        }
+        self.current_text_area
+            .set_block(Block::default().title(tile));
+        self.current_text_area
+            .widget()
+            .render(popup_layout.inner(&Margin::new(2, 2)), buf);
+        popup.render(popup_layout, buf);
+    }
+
+    fn render_debug_overlay(&self, area: Rect, buf: &mut Buffer) {
+        let Some(text) = self.selected_debug() else {
+            return;
+        };
+        let [_, bottom] =
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area);
+        let [_, overlay] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(bottom);
+        let block = Block::default()
+            .title("Debug")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(overlay, buf);
+        Paragraph::new(text).block(block).render(overlay, buf);
+    }
+
+    /// The newest entries of the action log in a panel along the bottom.
+    fn render_action_log(&self, area: Rect, buf: &mut Buffer) {
+        let [_, panel] =
+            Layout::vertical([Constraint::Percentage(65), Constraint::Percentage(35)]).areas(area);
+        let block = Block::default()
+            .title(format!(
+                "{} — {} {}",
+                tr(self.config.lang, "Log"),
+                self.action_log.len(),
+                tr(self.config.lang, "entries")
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        let shown = block.inner(panel).height as usize;
+        let skip = self.action_log.len().saturating_sub(shown);
+        let lines: Vec<Line> = self
+            .action_log
+            .iter()
+            .skip(skip)
+            .map(|(at, message)| {
+                Line::from(vec![
+                    at.format("%H:%M:%S ").to_string().dim(),
+                    Span::raw(message.as_str()),
+                ])
+            })
+            .collect();
+        Clear.render(panel, buf);
+        Paragraph::new(lines).block(block).render(panel, buf);
+    }
+
+    /// The position of the current Top within the agenda as (number, total),
+    /// for the selected Top or the one whose Anträge are shown.
+    fn agenda_progress(&self) -> Option<(usize, usize)> {
+        let tops = &self.tops_selected_sitzung.items;
+        let index = match self.layout {
+            SelectedLayout::Sitzungen => None,
+            SelectedLayout::Tops => self.tops_selected_sitzung.state.selected(),
+            SelectedLayout::Anträge => tops.iter().position(|top| top.id == self.top.id),
+        }?;
+        (index < tops.len()).then_some((index + 1, tops.len()))
+    }
+
+    fn render_trash(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(trash) = &mut self.trash else {
+            return;
+        };
+        let what = match trash.layout {
+            SelectedLayout::Sitzungen => "Sitzungen",
+            SelectedLayout::Tops => "Tops",
+            SelectedLayout::Anträge => "Anträge",
+        };
+        let deleted = tr(self.config.lang, "Deleted");
+        let title = if trash.loading {
+            format!(
+                "{} {} — {}",
+                deleted,
+                what,
+                tr(self.config.lang, "loading…")
+            )
+        } else {
+            format!("{} {} — {}", deleted, what, trash.items.items.len())
+        };
+        let items: Vec<ListItem> = trash
+            .items
+            .items
+            .iter()
+            .map(|deleted| {
+                let when = deleted
+                    .deleted_at
+                    .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                ListItem::new(format!("{}  {}", when, deleted.name))
+            })
+            .collect();
+        let theme = &self.config.theme;
+        render_list_popup(area, buf, theme, &title, items, &mut trash.items.state);
+    }
+
+    fn render_raw_json(&self, area: Rect, buf: &mut Buffer, raw: &RawJson) {
+        let popup_layout = centered_rect(80, 80, area);
+        let popup = Block::default()
+            .title(raw.url.clone())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        let text = raw.text.as_deref().unwrap_or("Loading…");
+        Clear.render(popup_layout, buf);
+        self.soft_wrap(Paragraph::new(text))
+            .block(popup)
+            .scroll((raw.scroll, 0))
+            .render(popup_layout, buf);
+        render_scrollbar(popup_layout, buf, text.lines().count(), raw.scroll as usize);
+    }
+
+    /// The selected item alone, centered, for reading or screenshots.
+    fn render_focus(&self, area: Rect, buf: &mut Buffer, scroll: u16) {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let text_lines = |text: &str| -> Vec<Line<'static>> {
+            text.trim()
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect()
+        };
+        let lines = match self.layout {
+            SelectedLayout::Sitzungen => self.sitzungen.selected_item().map(|sitzung| {
+                let mut lines = vec![
+                    Line::styled(sitzung.name.clone(), bold),
+                    Line::from(sitzung.datum.format("%Y-%m-%d %H:%M").to_string()).dim(),
+                ];
+                if !sitzung.anwesend.is_empty() {
+                    lines.push(Line::default());
+                    lines.push(Line::from(format!(
+                        "Anwesend: {}",
+                        sitzung.anwesend.join(", ")
+                    )));
+                }
+                lines
+            }),
+            SelectedLayout::Tops => self.tops_selected_sitzung.selected_item().map(|top| {
+                let mut lines = vec![Line::styled(top.name.clone(), bold)];
+                if let Some(verantwortlich) =
+                    top.verantwortlich.as_deref().filter(|v| !v.is_empty())
+                {
+                    lines.push(Line::from(format!("Vorgestellt von: {}", verantwortlich)).dim());
+                }
+                let inhalt = inhalt_to_text(&top.inhalt);
+                if !inhalt.trim().is_empty() {
+                    lines.push(Line::default());
+                    lines.extend(text_lines(&inhalt));
+                }
+                if !top.protokoll.trim().is_empty() {
+                    lines.push(Line::default());
+                    lines.push(Line::from("Protokoll").italic());
+                    lines.extend(text_lines(&top.protokoll));
+                }
+                lines
+            }),
+            SelectedLayout::Anträge => self
+                .anträge_selected_top
+                .selected_item()
+                .map(|antrag| antrag_detail_lines(antrag, &self.collapsed).0),
+        };
+        let lines = lines.unwrap_or_else(|| vec![Line::from("Nothing selected")]);
+        let [column] = Layout::horizontal([Constraint::Max(80)])
+            .flex(layout::Flex::Center)
+            .areas(area.inner(&Margin::new(2, 1)));
+        let rows: usize = lines
+            .iter()
+            .map(|line| line.width().max(1).div_ceil(column.width.max(1) as usize))
+            .sum();
+        let height = (rows as u16).min(column.height);
+        let [content] = Layout::vertical([Constraint::Length(height)])
+            .flex(layout::Flex::Center)
+            .areas(column);
+        let content = if scroll > 0 { column } else { content };
+        Block::default()
+            .bg(self.config.theme.row_bg)
+            .fg(self.config.theme.text)
+            .render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .render(content, buf);
+    }
+
+    fn render_agenda_summary(&self, area: Rect, buf: &mut Buffer, summary: &str) {
+        let popup_layout = centered_rect(80, 30, area);
+        let popup = Block::default()
+            .title(format!(
+                "{} — {}",
+                tr(self.config.lang, "Agenda"),
+                self.sitzung.name
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(popup_layout, buf);
+        Paragraph::new(summary.to_string())
+            .wrap(Wrap { trim: true })
+            .block(popup)
+            .render(popup_layout, buf);
+    }
+
+    fn render_protocol(&self, area: Rect, buf: &mut Buffer, protocol: &Protocol) {
+        let popup_layout = centered_rect(80, 90, area);
+        let popup = Block::default()
+            .title(format!(
+                "{} — {}",
+                tr(self.config.lang, "Protocol"),
+                self.sitzung.name
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        let lines = match &protocol.agenda {
+            None => vec![Line::from("Loading…")],
+            Some(Ok(agenda)) => {
+                let mut lines = protocol_lines(&self.sitzung, &agenda.tops);
+                if let Some(partial) = agenda.partial("Loaded") {
+                    lines.insert(0, Line::from(partial.red()));
+                }
+                lines
+            }
+            Some(Err(err)) => vec![Line::from(format!("Loading failed: {}", err))],
+        };
+        let len = lines.len();
+        Clear.render(popup_layout, buf);
+        self.soft_wrap(Paragraph::new(lines))
+            .block(popup)
+            .scroll((protocol.scroll, 0))
+            .render(popup_layout, buf);
+        render_scrollbar(popup_layout, buf, len, protocol.scroll as usize);
+    }
+
+    fn render_payload_preview(&self, area: Rect, buf: &mut Buffer) {
+        let text = self.payload_preview.clone().unwrap_or_default();
+        let popup_layout = centered_rect(60, 60, area);
+        let popup = Block::default()
+            .title(tr(self.config.lang, "Payload Preview"))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(popup_layout, buf);
+        self.soft_wrap(Paragraph::new(text))
+            .block(popup)
+            .render(popup_layout, buf);
+    }
+
+    fn render_import(&self, area: Rect, buf: &mut Buffer, step: &ImportStep) {
+        match step {
+            ImportStep::Prompt(prompt) => self.render_import_prompt(area, buf, prompt),
+            ImportStep::Confirm(entwürfe) => {
+                let broken = entwürfe.iter().filter(|e| e.is_err()).count();
+                let mut text = format!(
+                    "Create {} Anträge under '{}'?",
+                    entwürfe.len() - broken,
+                    self.top.name
+                );
+                if broken > 0 {
+                    text.push_str(&format!("\n{} entries could not be read.", broken));
+                }
+                text.push_str("\n\ny/Enter to start, n/Esc to abort");
+                self.render_text_popup(area, buf, "Import", text);
+            }
+            ImportStep::Running {
+                done, total, titel, ..
+            } => {
+                let template = tr(self.config.lang, "Creating Antrag {}/{}… {}");
+                let label = fill(template, &[&(done + 1), total, titel]);
+                let hint = tr(self.config.lang, "Esc to cancel");
+                self.render_progress(area, buf, "Import", label, (*done, *total), hint)
+            }
+            ImportStep::Report(report) => {
+                self.render_text_popup(area, buf, "Import", report.clone())
+            }
+        }
+    }
+
+    fn render_merge(&mut self, area: Rect, buf: &mut Buffer) {
+        if let Some(MergeStep::Target { source, targets }) = &mut self.merge {
+            let items = targets
+                .items
+                .iter()
+                .map(|sitzung| {
+                    ListItem::new(format!(
+                        "{}  {}",
+                        sitzung.datum.format("%d.%m.%Y"),
+                        sitzung.name
+                    ))
+                })
+                .collect();
+            let title =
+                tr(self.config.lang, "Move the Tops of '{}' to").replace("{}", &source.name);
+            let theme = &self.config.theme;
+            render_list_popup(area, buf, theme, &title, items, &mut targets.state);
+            return;
+        }
+        match &self.merge {
+            Some(MergeStep::Confirm { source, target }) => {
+                let text = format!(
+                    "Move all Tops of '{}' with their Anträge to the end of '{}'?\n\n\
+                     y/Enter to start, n/Esc to abort",
+                    source.name, target.name
+                );
+                self.render_text_popup(area, buf, "Merge", text);
+            }
+            Some(MergeStep::Running {
+                done, total, titel, ..
+            }) => {
+                let template = tr(self.config.lang, "Moving Top {}/{}… {}");
+                let label = fill(template, &[&(done + 1), total, titel]);
+                self.render_progress(area, buf, "Merge", label, (*done, *total), "");
+            }
+            Some(MergeStep::Report { report, .. }) => {
+                self.render_text_popup(area, buf, "Merge", report.clone());
+            }
+            Some(MergeStep::Target { .. }) | None => {}
+        }
+    }
+
+    fn render_progress(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        title: &str,
+        label: String,
+        (done, total): (usize, usize),
+        hint: &str,
+    ) {
+        let popup_layout = centered_rect(60, 20, area);
+        let popup = Block::default()
+            .title(tr(self.config.lang, title).to_string())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(popup_layout, buf);
+        let inner = popup.inner(popup_layout);
+        popup.render(popup_layout, buf);
+        let [label_area, gauge_area, hint_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+        Paragraph::new(label).render(label_area, buf);
+        let ratio = if total == 0 {
+            0.0
+        } else {
+            done as f64 / total as f64
+        };
+        Gauge::default()
+            .gauge_style(Style::default().fg(self.config.theme.selected_fg))
+            .ratio(ratio)
+            .render(gauge_area, buf);
+        Paragraph::new(hint).render(hint_area, buf);
+    }
+
+    fn render_import_prompt(&self, area: Rect, buf: &mut Buffer, prompt: &TextArea) {
+        let popup_layout = centered_rect(60, 20, area);
+        let popup = Block::default()
+            .title(tr(self.config.lang, "Import Anträge"))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(popup_layout, buf);
+        let inner = popup.inner(popup_layout);
+        popup.render(popup_layout, buf);
+        prompt.widget().render(inner, buf);
+    }
+
+    fn render_dashboard(&self, area: Rect, buf: &mut Buffer) {
+        let Some(dashboard) = &self.dashboard else {
+            return;
+        };
+        let theme = &self.config.theme;
+        let lang = self.config.lang;
+        Block::default().bg(theme.row_bg).render(area, buf);
+        let now = Local::now().naive_local();
+        let upcoming = upcoming_sitzungen(&self.all_sitzungen, now);
+        let next = match upcoming.first() {
+            Some(next) => {
+                let days = (next.datum.date() - now.date()).num_days();
+                let when = match days {
+                    0 => "today".to_string(),
+                    1 => "tomorrow".to_string(),
+                    days => format!("in {} days", days),
+                };
+                format!(
+                    "{}\n{}\n{}",
+                    next.name,
+                    next.datum.format("%Y-%m-%d %H:%M"),
+                    when
+                )
+            }
+            None => "none planned".to_string(),
+        };
+        let count = |count: fn(&backend::Stats) -> usize| match &dashboard.stats {
+            Some(Ok(stats)) => count(stats).to_string(),
+            Some(Err(_)) => "?".to_string(),
+            None => "…".to_string(),
+        };
+        let cards = [
+            (tr(lang, "Upcoming Sitzungen"), upcoming.len().to_string()),
+            (tr(lang, "Next Sitzung"), next),
+            (tr(lang, "Open Anträge"), count(|stats| stats.open)),
+            (
+                "Tops / Anträge",
+                format!(
+                    "{} / {}",
+                    count(|stats| stats.tops),
+                    count(|stats| stats.anträge)
+                ),
+            ),
+        ];
+        let [title_area, cards_area, message_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(6),
+            Constraint::Min(0),
+        ])
+        .areas(area);
+        Paragraph::new(tr(lang, "Dashboard").bold())
+            .centered()
+            .render(title_area, buf);
+        let card_areas = Layout::horizontal([Constraint::Ratio(1, 4); 4])
+            .spacing(1)
+            .split(cards_area);
+        for ((title, value), card_area) in cards.into_iter().zip(card_areas.iter()) {
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(theme.selected_fg));
+            Paragraph::new(value)
+                .centered()
+                .wrap(Wrap { trim: true })
+                .block(block)
+                .render(*card_area, buf);
+        }
+        let message = match &dashboard.stats {
+            Some(Err(err)) => format!("Counting failed: {}", err),
+            _ if dashboard.loading => "Counting Tops and Anträge…".to_string(),
+            Some(Ok(stats)) if !stats.failed.is_empty() => {
+                backend::partial("Counted", stats.tops, stats.failed.len()).unwrap_or_default()
+            }
+            _ => "Counts cover the upcoming Sitzungen".to_string(),
+        };
+        Paragraph::new(message.dim())
+            .centered()
+            .render(message_area, buf);
+    }
+
+    fn render_text_popup<'t>(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        title: &str,
+        text: impl Into<Text<'t>>,
+    ) {
+        let popup_layout = centered_rect(60, 60, area);
+        let popup = Block::default()
+            .title(tr(self.config.lang, title).to_string())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(self.config.theme.text))
+            .title_style(Style::default().fg(self.config.theme.text))
+            .style(
+                Style::default()
+                    .bg(self.config.theme.row_bg)
+                    .fg(self.config.theme.text),
+            );
+        Clear.render(popup_layout, buf);
+        Paragraph::new(text).block(popup).render(popup_layout, buf);
+    }
+}
+
+/// The first of `names` starting with `prefix`, ignoring case.
+fn prefix_match<'n>(names: impl Iterator<Item = &'n str>, prefix: &str) -> Option<usize> {
+    let prefix = prefix.to_lowercase();
+    if prefix.is_empty() {
+        return None;
+    }
+    names
+        .map(str::to_lowercase)
+        .position(|name| name.starts_with(&prefix))
+}
+
+/// The Tops in agenda order on one line, e.g. `1. Begrüßung · 2. Finanzen`.
+fn agenda_line(tops: &[Top]) -> String {
+    let mut tops: Vec<&Top> = tops.iter().collect();
+    tops.sort_by_key(|top| top.weight);
+    tops.iter()
+        .enumerate()
+        .map(|(index, top)| format!("{}. {}", index + 1, top.name))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// What `export::markdown` writes for a Sitzung, styled for the terminal.
+fn protocol_lines(sitzung: &Sitzung, agenda: &[(Top, Vec<Antrag>)]) -> Vec<Line<'static>> {
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut lines = vec![
+        Line::styled(
+            sitzung.name.clone(),
+            bold.add_modifier(Modifier::UNDERLINED),
+        ),
+        Line::from(sitzung.datum.format("%Y-%m-%d %H:%M").to_string()).dim(),
+    ];
+    if !sitzung.anwesend.is_empty() {
+        lines.push(Line::from(format!("Anwesend: {}", sitzung.anwesend.join(", "))).dim());
+    }
+    let paragraph = |lines: &mut Vec<Line<'static>>, text: &str| {
+        lines.extend(text.trim().lines().map(|line| Line::from(line.to_string())));
+    };
+    for (number, (top, anträge)) in agenda.iter().enumerate() {
+        lines.push(Line::default());
+        lines.push(Line::styled(
+            format!("Top {}: {}", number + 1, top.name),
+            bold.fg(tailwind::BLUE.c300),
+        ));
+        if let Some(verantwortlich) = top.verantwortlich.as_deref().filter(|v| !v.is_empty()) {
+            lines.push(Line::from(format!("Vorgestellt von: {}", verantwortlich)).dim());
+        }
+        let inhalt = inhalt_to_text(&top.inhalt);
+        if !inhalt.trim().is_empty() {
+            lines.push(Line::default());
+            paragraph(&mut lines, &inhalt);
+        }
+        for antrag in anträge {
+            lines.push(Line::default());
+            lines.push(Line::from(vec![
+                Span::styled(antrag.titel.clone(), bold),
+                Span::raw(" "),
+                Span::styled(
+                    format!("({})", antrag.status.label()),
+                    Style::default().fg(antrag.status.color()),
+                ),
+            ]));
+            if !antrag.begründung.trim().is_empty() {
+                lines.push(Line::from("Begründung").italic());
+                paragraph(&mut lines, &antrag.begründung);
+            }
+            lines.push(Line::from("Antragstext").italic());
+            paragraph(&mut lines, &antrag.antragstext);
+        }
+        if !top.protokoll.trim().is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from("Protokoll").italic());
+            paragraph(&mut lines, &top.protokoll);
+        }
+    }
+    lines
+}
+
+/// Line diff of every param whose text differs from the loaded value.
+fn diff_lines(original: &[Param], current: &[Param]) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    for param in current {
+        let before = original
+            .iter()
+            .find(|p| p.key == param.key)
+            .map_or("", |p| p.text.as_str());
+        if before == param.text {
+            continue;
+        }
+        lines.push(Line::from(param.titel.clone().bold()));
+        for change in TextDiff::from_lines(before, &param.text).iter_all_changes() {
+            let text = change.to_string_lossy().trim_end_matches('\n').to_string();
+            lines.push(match change.tag() {
+                ChangeTag::Delete => Line::from(format!("- {}", text).red()),
+                ChangeTag::Insert => Line::from(format!("+ {}", text).green()),
+                ChangeTag::Equal => Line::from(format!("  {}", text).dim()),
+            });
+        }
+        lines.push(Line::default());
+    }
+    if lines.is_empty() {
+        lines.push(Line::from("No changes"));
+    }
+    lines
+}
+
+fn is_required(layout: &SelectedLayout, key: &str) -> bool {
+    // Anträge added while creating a Top follow the Antrag schema.
+    let (layout, key) = match key
+        .strip_prefix("antrag.")
+        .and_then(|rest| rest.split_once('.'))
+    {
+        Some((_, key)) => (&SelectedLayout::Anträge, key),
+        None => (layout, key),
+    };
+    REQUIRED_FIELDS
+        .iter()
+        .any(|(kind, keys)| kind == layout && keys.contains(&key))
+}
+
+/// Labels of the required params that are still empty.
+fn missing_required<'p>(layout: &SelectedLayout, params: &'p [Param]) -> Vec<&'p str> {
+    params
+        .iter()
+        .filter(|param| param.text.trim().is_empty() && is_required(layout, &param.key))
+        .map(|param| param.titel.as_str())
+        .collect()
+}
+
+/// An empty required field, or a date `datum::parse` cannot read.
+fn is_invalid(layout: &SelectedLayout, param: &Param) -> bool {
+    let empty = param.text.trim().is_empty();
+    if empty {
+        return is_required(layout, &param.key);
+    }
+    param.key == "datum" && datum::parse(&param.text, Local::now().naive_local()).is_none()
+}
+
+/// The first invalid param after `from`, or before it going backwards,
+/// wrapping around. Without `from` the search starts at the ends.
+fn next_invalid(
+    layout: &SelectedLayout,
+    params: &[Param],
+    from: Option<usize>,
+    forward: bool,
+) -> Option<usize> {
+    let len = params.len();
+    let steps = (1..=len).map(|step| match (from, forward) {
+        (Some(from), true) => (from + step) % len,
+        (Some(from), false) => (from + len * 2 - step) % len,
+        (None, true) => step - 1,
+        (None, false) => len - step,
+    });
+    steps.into_iter().find(|&i| is_invalid(layout, &params[i]))
+}
+
+/// A row of the editor, required fields get a `*` and their own color.
+fn param_item<'p>(layout: &SelectedLayout, param: &'p Param) -> ListItem<'p> {
+    let label = if is_required(layout, &param.key) {
+        format!("{}*: ", param.titel).fg(tailwind::ROSE.c400)
+    } else {
+        format!("{}: ", param.titel).into()
+    };
+    ListItem::new(Line::from(vec![label, Span::raw(param.text.as_str())]))
+}
+
+fn new_sitzung_params() -> Vec<Param> {
+    vec![
+        Param::new("Datum", "datum", ""),
+        Param::new("Name", "name", ""),
+    ]
+}
+
+fn sitzung_params(sitzung: &Sitzung) -> Vec<Param> {
+    vec![
+        Param::new("Datum", "datum", sitzung.datum.to_string()),
+        Param::new("Name", "name", sitzung.name.clone()),
+    ]
+}
+
+fn new_top_params() -> Vec<Param> {
+    vec![
+        Param::new("Titel", "titel", ""),
+        Param::new("Inhalt", "inhalt", ""),
+        Param::new("Verantwortlich", "verantwortlich", ""),
+    ]
+}
+
+fn top_params(top: &Top) -> Vec<Param> {
+    let verantwortlich = top.verantwortlich.clone().unwrap_or_default();
+    vec![
+        Param::new("Titel", "titel", top.name.clone()),
+        Param::new("Inhalt", "inhalt", inhalt_to_text(&top.inhalt)),
+        Param::new("Verantwortlich", "verantwortlich", verantwortlich),
+    ]
+}
+
+/// The Tops whose weight differs from their position, with that position
+/// as the new weight.
+fn renumbered_weights(tops: &[Top]) -> Vec<(Uuid, i32)> {
+    tops.iter()
+        .zip(0..)
+        .filter(|(top, weight)| top.weight != *weight)
+        .map(|(top, weight)| (top.id, weight))
+        .collect()
+}
+
+/// The configured members in their order, then anyone recorded as present
+/// who is not on the roster, each with whether they were present.
+fn attendance_roster(members: &[String], present: &[String]) -> Vec<(String, bool)> {
+    let mut roster: Vec<(String, bool)> = members
+        .iter()
+        .map(|name| (name.clone(), present.contains(name)))
+        .collect();
+    for name in present {
+        if !members.contains(name) {
+            roster.push((name.clone(), true));
+        }
+    }
+    roster
+}
+
+/// `base` ends in a slash, which a template written as `{base}/...` would
+/// double.
+fn agenda_link(template: &str, base: &str, id: Uuid) -> String {
+    template
+        .replace("{base}/", base)
+        .replace("{base}", base)
+        .replace("{id}", &id.to_string())
+}
+
+fn fill_template(template: &str, sitzung: &Sitzung, top: &Top) -> String {
+    template
+        .replace("{sitzung}", &sitzung.name)
+        .replace("{datum}", &sitzung.datum.format("%Y-%m-%d").to_string())
+        .replace("{top}", &top.name)
+}
+
+/// A spinner frame with the seconds spent loading so far.
+fn loading_label(elapsed: Duration, lang: Lang) -> String {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    let frame = FRAMES[(elapsed.as_millis() / 100) as usize % FRAMES.len()];
+    let seconds = format!("{:.1}", elapsed.as_secs_f32());
+    fill(
+        tr(lang, " — {} loading {}s, Esc to cancel"),
+        &[&frame, &seconds],
+    )
+}
+
+/// Whether `code` in `layout` sends a request to the backend.
+fn fetches(layout: &SelectedLayout, code: KeyCode) -> bool {
+    use KeyCode::Char;
+    match layout {
+        SelectedLayout::Sitzungen => matches!(code, Char('o' | 'e' | 'T')),
+        SelectedLayout::Tops => matches!(code, Char('o' | 'e' | '[' | ']')),
+        SelectedLayout::Anträge => matches!(code, Char('e' | '[' | ']')),
+    }
+}
+
+fn agenda_label(current: usize, total: usize, lang: Lang) -> String {
+    let percent = current * 100 / total;
+    fill(
+        tr(lang, "Top {} of {} — {}%"),
+        &[&current, &total, &percent],
+    )
+}
+
+/// The Anträge whose author contains `author`, ignoring case, each with
+/// its Top.
+fn anträge_by_author(agenda: Vec<(Top, Vec<Antrag>)>, author: &str) -> Vec<(Top, Antrag)> {
+    let author = author.to_lowercase();
+    agenda
+        .into_iter()
+        .flat_map(|(top, anträge)| anträge.into_iter().map(move |a| (top.clone(), a)))
+        .filter(|(_, antrag)| {
+            antrag
+                .author
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&author))
+        })
+        .collect()
+}
+
+/// The Sitzungen from the day of `now` on, the next one first.
+fn upcoming_sitzungen(sitzungen: &[Sitzung], now: NaiveDateTime) -> Vec<&Sitzung> {
+    let mut upcoming: Vec<&Sitzung> = sitzungen
+        .iter()
+        .filter(|s| s.datum.date() >= now.date())
+        .collect();
+    upcoming.sort_by_key(|s| s.datum);
+    upcoming
+}
+
+/// The index of the Sitzung taking place on the day of `now` and `true`, or
+/// else of the next upcoming one and `false`.
+fn find_todays_sitzung(sitzungen: &[Sitzung], now: NaiveDateTime) -> Option<(usize, bool)> {
+    let today = now.date();
+    if let Some(index) = sitzungen.iter().position(|s| s.datum.date() == today) {
+        return Some((index, true));
+    }
+    sitzungen
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.datum > now)
+        .min_by_key(|(_, s)| s.datum)
+        .map(|(index, _)| (index, false))
+}
+
+/// How a Top's `inhalt` is shown in the editor: nothing for null, strings
+/// without quotes and objects or arrays pretty-printed.
+fn inhalt_to_text(inhalt: &serde_json::Value) -> String {
+    use serde_json::Value;
+    match inhalt {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::to_string_pretty(inhalt).unwrap_or_default()
+        }
+        Value::Bool(_) | Value::Number(_) => inhalt.to_string(),
+    }
+}
+
+/// Turns edited text back into a value of the same JSON type as `original`,
+/// falling back to a string if the text does not fit that type.
+fn inhalt_from_text(text: &str, original: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    let parsed = serde_json::from_str::<Value>(text).ok();
+    match (original, parsed) {
+        (Value::Null, _) if text.is_empty() => Value::Null,
+        (Value::Bool(_), Some(value @ Value::Bool(_)))
+        | (Value::Number(_), Some(value @ Value::Number(_)))
+        | (Value::Array(_), Some(value @ Value::Array(_)))
+        | (Value::Object(_), Some(value @ Value::Object(_))) => value,
+        _ => Value::String(text.to_string()),
+    }
+}
+
+fn new_antrag_params() -> Vec<Param> {
+    vec![
+        Param::new("Titel", "titel", ""),
+        Param::new("Begründung", "begründung", ""),
+        Param::new("Antragstext", "antragstext", ""),
+        Param::new("Antragssteller", "antragssteller", ""),
+        Param::new("Anhänge", "anhänge", ""),
+    ]
+}
+
+fn antrag_params(antrag: &Antrag) -> Vec<Param> {
+    vec![
+        Param::new("Titel", "titel", antrag.titel.clone()),
+        Param::new("Begründung", "begründung", antrag.begründung.clone()),
+        Param::new("Antragstext", "antragstext", antrag.antragstext.clone()),
+        Param::new("Anhänge", "anhänge", antrag.anhänge.join("\n")),
+    ]
+}
+
+/// Payload for the params of the edited object itself, leaving out inline
+/// Anträge (keys like `antrag.1.titel`).
+fn params_payload(params: &[Param]) -> serde_json::Map<String, serde_json::Value> {
+    params
+        .iter()
+        .filter(|param| !param.key.contains('.'))
+        .map(|param| {
+            let value = match param.key.as_str() {
+                "anhänge" => serde_json::json!(split_anhänge(&param.text)),
+                // An empty field takes the responsible person off the Top.
+                "verantwortlich" if param.text.trim().is_empty() => serde_json::Value::Null,
+                _ => serde_json::Value::String(param.text.clone()),
+            };
+            (param.key.clone(), value)
+        })
+        .collect()
+}
+
+/// Top-level payload fields the app fills in itself, never taken from params.
+const RESERVED_KEYS: &[&str] = &["id", "sitzung_id", "status", "updated_at"];
+
+/// Adds the params to `data` without touching `RESERVED_KEYS`. The text of
+/// `inhalt` is turned back into the JSON type of `original_inhalt` and stays
+/// nested under `inhalt`, so its own `id` or `titel` cannot clash.
+/// The body of the PATCH for the item being edited: its ids, what the
+/// backend needs besides the params, the params and the version.
+/// `anhang` if it is an http or https URL. Anhänge come from the backend,
+/// anything else, e.g. a `file://` path, is not handed to the system.
+fn web_link(anhang: &str) -> Option<reqwest::Url> {
+    let url = reqwest::Url::parse(anhang.trim()).ok()?;
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+/// Separates the entries that could be read from the errors of the others,
+/// numbered by their place in the file.
+fn split_unreadable(
+    entwürfe: Vec<Result<import::Entwurf, String>>,
+) -> (Vec<import::Entwurf>, Vec<String>) {
+    let mut readable = vec![];
+    let mut unreadable = vec![];
+    for (index, entwurf) in entwürfe.into_iter().enumerate() {
+        match entwurf {
+            Ok(entwurf) => readable.push(entwurf),
+            Err(err) => unreadable.push(format!("Eintrag {}: {}", index + 1, err)),
+        }
+    }
+    (readable, unreadable)
+}
+
+fn edit_payload(
+    editing: Option<&SelectedLayout>,
+    sitzung: &Sitzung,
+    top: Option<&Top>,
+    antrag: &Antrag,
+    params: &[Param],
+    version: Option<&str>,
+) -> serde_json::Value {
+    let mut data = serde_json::json!({});
+    match editing {
+        Some(SelectedLayout::Sitzungen) => {
+            data["id"] = serde_json::Value::String(sitzung.id.to_string());
+        }
+        Some(SelectedLayout::Tops) => {
+            if let Some(top) = top {
+                data["id"] = serde_json::Value::String(top.id.to_string());
+            }
+            data["sitzung_id"] = serde_json::Value::String(sitzung.id.to_string());
+        }
+        Some(SelectedLayout::Anträge) => {
+            data["id"] = serde_json::Value::String(antrag.id.to_string());
+            data["status"] = serde_json::json!(antrag.status);
+        }
+        None => {}
+    }
+    let inhalt = match editing {
+        Some(SelectedLayout::Tops) => top.map(|top| &top.inhalt),
+        _ => None,
+    };
+    merge_params(&mut data, params, inhalt);
+    if let (Some(_), Some(version)) = (editing, version) {
+        data["updated_at"] = serde_json::Value::String(version.to_string());
+    }
+    data
+}
+
+fn merge_params(
+    data: &mut serde_json::Value,
+    params: &[Param],
+    original_inhalt: Option<&serde_json::Value>,
+) {
+    for (key, value) in params_payload(params) {
+        if RESERVED_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        data[key] = value;
+    }
+    if let (Some(original), Some(text)) = (original_inhalt, data["inhalt"].as_str()) {
+        data["inhalt"] = inhalt_from_text(text, original);
+    }
+}
+
+/// Groups the `antrag.<n>.<key>` params added while creating a Top into
+/// one payload per Antrag.
+fn inline_antrag_payloads(params: &[Param]) -> Vec<serde_json::Map<String, serde_json::Value>> {
+    let mut anträge: Vec<serde_json::Map<String, serde_json::Value>> = vec![];
+    for param in params {
+        let Some((number, key)) = inline_antrag_key(&param.key) else {
+            continue;
+        };
+        if anträge.len() < number {
+            anträge.resize(number, serde_json::Map::new());
+        }
+        anträge[number - 1].insert(
+            key.to_string(),
+            serde_json::Value::String(param.text.clone()),
+        );
+    }
+    anträge
+}
+
+/// The number of the inline Antrag a param of the Top being created
+/// belongs to, and its key within that Antrag.
+fn inline_antrag_key(key: &str) -> Option<(usize, &str)> {
+    let (number, key) = key.strip_prefix("antrag.")?.split_once('.')?;
+    Some((number.parse().ok()?, key))
+}
+
+/// Removes the inline Antrag the param at `index` belongs to and moves the
+/// ones after it up a number. False if that param is not part of one.
+fn remove_inline_antrag(params: &mut Vec<Param>, index: usize) -> bool {
+    let Some((removed, _)) = params
+        .get(index)
+        .and_then(|param| inline_antrag_key(&param.key))
+    else {
+        return false;
+    };
+    params.retain(|param| inline_antrag_key(&param.key).map(|(n, _)| n) != Some(removed));
+    for param in params.iter_mut() {
+        let Some((number, key)) = inline_antrag_key(&param.key) else {
+            continue;
+        };
+        if number > removed {
+            let old = format!("Antrag {}: ", number);
+            let new = format!("Antrag {}: ", number - 1);
+            param.key = format!("antrag.{}.{}", number - 1, key);
+            param.titel = param.titel.replacen(&old, &new, 1);
+        }
+    }
+    true
+}
+
+fn split_anhänge(text: &str) -> Vec<String> {
+    text.split_whitespace().map(str::to_string).collect()
+}
+
+/// When and by whom `antrag` was last changed, as far as the backend says.
+fn edited(antrag: &Antrag) -> Option<String> {
+    let at = antrag
+        .updated_at
+        .as_deref()
+        .map(|at| format_timestamp(at, &Local));
+    match (at, antrag.modified_by.as_deref()) {
+        (Some(at), Some(by)) => Some(format!("{} von {}", at, by)),
+        (None, Some(by)) => Some(format!("von {}", by)),
+        (at, None) => at,
+    }
+}
+
+/// The Antrag details as lines, together with the line of each section
+/// heading. Collapsed sections show only their heading.
+fn antrag_detail_lines(
+    antrag: &Antrag,
+    collapsed: &Collapsed,
+) -> (Vec<Line<'static>>, Vec<(usize, Section)>) {
+    let mut lines = vec![
+        Line::from(antrag.titel.clone().bold()),
+        Line::from(vec![
+            Span::raw("Status: "),
+            Span::styled(
+                antrag.status.label(),
+                Style::default().fg(antrag.status.color()),
+            ),
+        ]),
+    ];
+    if let Some(typ) = antrag.typ.as_deref().filter(|typ| !typ.trim().is_empty()) {
+        lines.push(Line::from(format!("Typ: {}", typ)));
+    }
+    let timestamp = |at: &Option<String>| at.as_deref().map(|at| format_timestamp(at, &Local));
+    let metadata = [
+        ("Autor", antrag.author.clone()),
+        ("Erstellt", timestamp(&antrag.created_at)),
+        ("Geändert", edited(antrag)),
+        ("ID", Some(antrag.id.to_string())),
+    ];
+    let metadata: Vec<Line> = metadata
+        .into_iter()
+        .filter_map(|(label, value)| {
+            let value = value.filter(|value| !value.trim().is_empty())?;
+            Some(Line::from(vec![
+                Span::styled(format!("{:<10}", label), Style::default().dim()),
+                Span::raw(value),
+            ]))
+        })
+        .collect();
+    let text = |text: &str| text.lines().map(|l| Line::from(l.to_string())).collect();
+    let anhänge = antrag
+        .anhänge
+        .iter()
+        .enumerate()
+        .map(|(i, url)| Line::from(format!("{}. {}", i + 1, url)))
+        .collect();
+    let sections = [
+        (Section::Metadaten, metadata),
+        (Section::Begründung, text(&antrag.begründung)),
+        (Section::Antragstext, text(&antrag.antragstext)),
+        (Section::Anhänge, anhänge),
+    ];
+    let mut headings = vec![];
+    for (section, body) in sections {
+        if section == Section::Anhänge && body.is_empty() {
+            continue;
+        }
+        lines.push(Line::from(""));
+        headings.push((lines.len(), section));
+        if collapsed.get(section) {
+            lines.push(Line::from(vec![
+                format!("▸ {}", section.title()).bold(),
+                format!(" ({} lines)", body.len()).dim(),
+            ]));
+        } else {
+            lines.push(Line::from(format!("▾ {}", section.title()).bold()));
+            lines.extend(body);
+        }
+    }
+    (lines, headings)
+}
 
-        if let Some(editing) = &self.currently_creating {
-            let title = match editing {
-                SelectedLayout::Sitzungen => "Create Sitzung",
-                SelectedLayout::Tops => "Create Top",
-                SelectedLayout::Anträge => "Create Antrag",
-            };
-            let outer_block = Block::default()
-                .borders(Borders::NONE)
-                .fg(TEXT_COLOR)
-                .bg(TODO_HEADER_BG)
-                .title(title)
-                .title_alignment(Alignment::Center);
-            let inner_block = Block::default()
-                .borders(Borders::NONE)
-                .fg(TEXT_COLOR)
-                .bg(NORMAL_ROW_COLOR);
+/// The section of the details that `headings` places at `line`, the first
+/// one if `line` is above all of them.
+fn section_at(headings: &[(usize, Section)], line: usize) -> Option<Section> {
+    headings
+        .iter()
+        .rev()
+        .find(|(heading, _)| *heading <= line)
+        .or(headings.first())
+        .map(|(_, section)| *section)
+}
 
-            let outer_area = area;
-            let inner_area = outer_block.inner(outer_area);
+/// Shows a backend timestamp in the time zone `tz`. Timestamps without an
+/// offset are taken as UTC, anything unreadable is shown as it is.
+fn format_timestamp<Tz: TimeZone>(text: &str, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let parsed = DateTime::parse_from_rfc3339(text)
+        .map(|at| at.with_timezone(&Utc))
+        .or_else(|_| text.parse::<NaiveDateTime>().map(|at| at.and_utc()));
+    match parsed {
+        Ok(at) => at.with_timezone(tz).format("%Y-%m-%d %H:%M").to_string(),
+        Err(_) => text.to_string(),
+    }
+}
 
-            outer_block.render(outer_area, buf);
+/// The columns of the Anträge table after the number, in order, with
+/// their width if fixed. The Titel comes first and cannot be hidden.
+fn antrag_columns(
+    table: &config::AntragTable,
+    anträge: &[Antrag],
+) -> Vec<(AntragColumn, Option<u16>)> {
+    let default_width = |column| match column {
+        AntragColumn::Titel => None,
+        AntragColumn::Antragssteller => Some(20),
+        AntragColumn::Status => Some(15),
+        AntragColumn::Erstellt => Some(10),
+    };
+    let created = anträge.iter().any(|antrag| antrag.created_at.is_some());
+    [
+        AntragColumn::Titel,
+        AntragColumn::Antragssteller,
+        AntragColumn::Status,
+        AntragColumn::Erstellt,
+    ]
+    .into_iter()
+    .filter(|column| *column == AntragColumn::Titel || !table.hide.contains(column))
+    .filter(|column| *column != AntragColumn::Erstellt || created)
+    .map(|column| {
+        let width = table.widths.get(&column).copied();
+        (column, width.or(default_width(column)))
+    })
+    .collect()
+}
 
-            let mut listelement: Vec<String> = vec![];
-            listelement = self
-                .edit_buffer
-                .items
-                .iter()
-                .map(|p| format!("{}: {}", p.titel, p.text))
-                .collect();
+/// The first three letters of an Antrag type for its badge, `—` without one.
+fn typ_abbreviation(typ: Option<&str>) -> String {
+    match typ.map(str::trim).filter(|typ| !typ.is_empty()) {
+        Some(typ) => {
+            let short = take_width(&typ.to_uppercase(), 3);
+            let padding = 3 - short.width();
+            format!("{}{}", short, " ".repeat(padding))
+        }
+        None => " — ".to_string(),
+    }
+}
 
-            let items = List::new(listelement)
-                .block(inner_block)
-                .highlight_style(
-                    Style::default()
-                        .add_modifier(Modifier::BOLD)
-                        .add_modifier(Modifier::REVERSED)
-                        .fg(SELECTED_STYLE_FG),
-                )
-                .highlight_symbol(">")
-                .highlight_spacing(HighlightSpacing::Always);
-            StatefulWidget::render(items, inner_area, buf, &mut self.edit_buffer.state);
+/// `matched` are the characters of the title to emphasize for the filter.
+fn antrag_list_item(
+    number: usize,
+    antrag: &Antrag,
+    width: usize,
+    wrap: bool,
+    matched: &[usize],
+    typ_colors: &HashMap<String, Color>,
+) -> ListItem<'static> {
+    let number = format!("{}. ", number);
+    let typ = format!(" {} ", typ_abbreviation(antrag.typ.as_deref()));
+    let typ_color = antrag
+        .typ
+        .as_ref()
+        .and_then(|typ| typ_colors.get(typ.trim()))
+        .copied()
+        .unwrap_or(tailwind::SLATE.c600);
+    let badge = format!(" {} ", antrag.status.label());
+    let indent = number.width() + typ.width() + 1 + badge.width() + 1;
+    let titel = fit_to_width(&antrag.titel, width.saturating_sub(indent), wrap);
+    let mut titel = emphasize_matches(titel, matched).into_iter();
+    let mut first = vec![
+        Span::raw(number),
+        Span::styled(
+            typ,
+            Style::default()
+                .fg(Color::Black)
+                .bg(typ_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            badge,
+            Style::default()
+                .fg(Color::Black)
+                .bg(antrag.status.color())
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" "),
+    ];
+    first.extend(titel.next().map(|line| line.spans).unwrap_or_default());
+    let mut lines = vec![Line::from(first)];
+    lines.extend(titel.map(|mut line| {
+        line.spans.insert(0, Span::raw(" ".repeat(indent)));
+        line
+    }));
+    let item = ListItem::new(lines);
+    if antrag.status == AntragStatus::Zurückgezogen {
+        item.style(Style::default().add_modifier(Modifier::DIM))
+    } else {
+        item
+    }
+}
+
+/// Cuts `text` to `width` columns with a trailing ellipsis, or with `wrap`
+/// breaks it at spaces into lines of at most `width` columns. Always returns
+/// at least one line. Counts display columns of whole graphemes, so umlauts
+/// and emoji are neither split nor miscounted.
+fn fit_to_width(text: &str, width: usize, wrap: bool) -> Vec<String> {
+    let width = width.max(1);
+    if text.width() <= width {
+        return vec![text.to_string()];
+    }
+    if !wrap {
+        let mut cut = take_width(text, width - 1);
+        cut.push('…');
+        return vec![cut];
+    }
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        if !line.is_empty() && line.width() + 1 + word.width() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        for g in word.graphemes(true) {
+            if !line.is_empty() && line.width() + g.width() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(g);
         }
     }
+    lines.push(line);
+    lines
+}
 
-    fn render_edit_param(&mut self, area: Rect, buf: &mut Buffer) {
-        let popup_layout = centered_rect(50, 50, area);
-        let popup = Block::default()
-            .title("Edit Value")
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(TEXT_COLOR))
-            .title_style(Style::default().fg(TEXT_COLOR))
-            .style(Style::default().bg(NORMAL_ROW_COLOR).fg(TEXT_COLOR));
-        let param = self.edit_param_pop.as_ref().unwrap();
-        let tile = &param.titel;
-        let text = &param.text;
-        self.current_text_area
-            .set_block(Block::default().title(tile.clone()));
-        self.current_text_area
-            .widget()
-            .render(popup_layout.inner(&Margin::new(2, 2)), buf);
-        popup.render(popup_layout, buf);
+/// The longest prefix of `text` that fits in `width` columns, whole graphemes only.
+fn take_width(text: &str, width: usize) -> String {
+    let mut cut = String::new();
+    for g in text.graphemes(true) {
+        if cut.width() + g.width() > width {
+            break;
+        }
+        cut.push_str(g);
+    }
+    cut
+}
+
+fn render_scrollbar(area: Rect, buf: &mut Buffer, len: usize, position: usize) {
+    if len <= area.height as usize {
+        return;
     }
+    let mut state = ScrollbarState::new(len).position(position);
+    Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .render(area, buf, &mut state);
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -861,23 +7039,200 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn render_title(area: Rect, buf: &mut Buffer) {
-    Paragraph::new("Ratatui List Example")
-        .bold()
+/// Where an Antrag belongs, e.g. `Sitzung 3 (2024-05-02) › Finanzen › Titel`.
+fn breadcrumb(sitzung: &Sitzung, top: &Top, titel: &str) -> String {
+    format!(
+        "{} ({}) › {} › {}",
+        sitzung.name,
+        sitzung.datum.format("%Y-%m-%d"),
+        top.name,
+        titel
+    )
+}
+
+/// `profile` is the active backend profile, if any are configured, and
+/// `session` the time left until the token expires.
+fn render_title(
+    area: Rect,
+    buf: &mut Buffer,
+    title: &str,
+    insecure: bool,
+    profile: Option<&str>,
+    session: Option<Span<'static>>,
+    version_warning: Option<&str>,
+) {
+    let mut title = vec![title.to_string().bold()];
+    if let Some(profile) = profile {
+        title.push(format!(" [{}]", profile).fg(tailwind::AMBER.c400));
+    }
+    title.extend(session);
+    let mut lines = vec![Line::from(title)];
+    if insecure {
+        lines.push(Line::from(
+            format!("⚠ TLS CERTIFICATES ARE NOT CHECKED for {}", base_url())
+                .bold()
+                .white()
+                .on_red(),
+        ));
+    }
+    if let Some(warning) = version_warning {
+        lines.push(Line::from(
+            format!("⚠ {}", warning).bold().black().on_yellow(),
+        ));
+    }
+    // Warnings matter more than the title if not all lines fit.
+    let hidden = lines.len().saturating_sub(area.height as usize);
+    lines.drain(..hidden);
+    Paragraph::new(lines).centered().render(area, buf);
+}
+
+/// The first line of the `selected` row of a list scrolled to `offset`
+/// whose rows are `heights` tall, `None` if it is not on screen.
+fn row_area(area: Rect, heights: &[u16], offset: usize, selected: usize) -> Option<Rect> {
+    let above: u16 = heights.get(offset..selected)?.iter().sum();
+    (above < area.height).then_some(Rect {
+        y: area.y + above,
+        height: 1,
+        ..area
+    })
+}
+
+/// The length and position in lines for the scrollbar of a list scrolled to
+/// `offset` whose rows are `heights` tall.
+fn scroll_rows(heights: &[u16], offset: usize) -> (usize, usize) {
+    let line = |rows: &[u16]| rows.iter().map(|&height| height as usize).sum();
+    (line(heights), line(&heights[..offset.min(heights.len())]))
+}
+
+/// The time left until the token expires, colored by how urgent it is.
+fn session_span(left: Duration, lang: Lang) -> Span<'static> {
+    let label = format!(" · {}", session_label(left, lang));
+    match left.as_secs() {
+        0 => label.fg(tailwind::RED.c400),
+        1..=119 => label.fg(tailwind::AMBER.c400),
+        _ => label.dim(),
+    }
+}
+
+fn session_label(left: Duration, lang: Lang) -> String {
+    let secs = left.as_secs();
+    let time = match secs {
+        1..=59 => format!("{}s", secs),
+        _ => format!("{}m", secs.div_ceil(60)),
+    };
+    match (lang, secs) {
+        (Lang::En, 0) => "session expired".to_string(),
+        (Lang::En, _) => format!("session expires in {}", time),
+        (Lang::De, 0) => "Anmeldung abgelaufen".to_string(),
+        (Lang::De, _) => format!("Anmeldung läuft in {} ab", time),
+    }
+}
+
+/// `"1.4"`, `"v2.0.1"` or `"1.2.3-beta"`, missing parts count as 0.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().transpose().ok()?.unwrap_or(0);
+    let patch = parts.next().transpose().ok()?.unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+/// What to warn about if the backend reports `version`, `None` if it is
+/// one this client was tested against.
+fn version_warning(version: &str) -> Option<String> {
+    let (low, high) = (COMPATIBLE_BACKEND.start, COMPATIBLE_BACKEND.end);
+    match parse_version(version) {
+        Some(parsed) if COMPATIBLE_BACKEND.contains(&parsed) => None,
+        Some(_) => Some(format!(
+            "Backend {} is untested, expected {}.{}.{} up to {}.{}.{}",
+            version, low.0, low.1, low.2, high.0, high.1, high.2
+        )),
+        None => Some(format!("Backend reports unknown version '{}'", version)),
+    }
+}
+
+fn render_jump_prompt(area: Rect, buf: &mut Buffer, digits: &str) {
+    Paragraph::new(format!("Go to: {}", digits))
+        .centered()
+        .render(area, buf);
+}
+
+fn render_list_popup(
+    area: Rect,
+    buf: &mut Buffer,
+    theme: &config::Theme,
+    title: &str,
+    items: Vec<ListItem>,
+    state: &mut ListState,
+) {
+    let popup_layout = centered_rect(60, 60, area);
+    let popup = Block::default()
+        .title(title.to_string())
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.text))
+        .title_style(Style::default().fg(theme.text))
+        .style(Style::default().bg(theme.row_bg).fg(theme.text));
+    let list = List::new(items)
+        .block(popup)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::REVERSED)
+                .fg(theme.selected_fg),
+        )
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+    Clear.render(popup_layout, buf);
+    StatefulWidget::render(list, popup_layout, buf, state);
+}
+
+fn render_prompt(area: Rect, buf: &mut Buffer, label: &str, text: &str) {
+    Paragraph::new(format!("{}: {}▏", label, text))
+        .centered()
+        .render(area, buf);
+}
+
+fn render_status(area: Rect, buf: &mut Buffer, message: &str, color: Color) {
+    let area = Rect { height: 1, ..area };
+    Clear.render(area, buf);
+    Paragraph::new(message.to_string())
+        .fg(color)
         .centered()
         .render(area, buf);
 }
 
-fn render_footer(area: Rect, buf: &mut Buffer) {
-    Paragraph::new("\nUse ↓↑ to move, o to open, p to create a new entry, e to edit and q/ESC to exit, d to delete")
+fn render_too_small(area: Rect, buf: &mut Buffer, lang: Lang) {
+    let [area] = Layout::vertical([Constraint::Length(2)])
+        .flex(layout::Flex::Center)
+        .areas(area);
+    let template = tr(lang, "Terminal too small\nneeds at least {}x{}");
+    Paragraph::new(fill(template, &[&MIN_WIDTH, &MIN_HEIGHT]))
         .centered()
         .render(area, buf);
 }
 
+fn render_footer(
+    area: Rect,
+    buf: &mut Buffer,
+    hints: &[Hint],
+    keymap: &config::KeyMap,
+    lang: Lang,
+) {
+    let text = hints
+        .iter()
+        .map(|(key, action)| format!("{} {}", tr(lang, &keymap.label(key)), tr(lang, action)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Paragraph::new(text).centered().render(area, buf);
+}
+
 impl<T> StatefulList<T> {
     fn with_items(items: Vec<T>) -> Self {
         let mut state = ListState::default();
-        state.select(Some(0));
+        state.select((!items.is_empty()).then_some(0));
 
         Self {
             state,
@@ -887,20 +7242,29 @@ impl<T> StatefulList<T> {
     }
 
     fn next(&mut self) {
+        if self.items.is_empty() {
+            self.state.select(None);
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
-                if self.items.len() == 1 || self.items.len() - 1 == i {
+                if i + 1 >= self.items.len() {
                     0
                 } else {
                     i + 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            // The list may have shrunk since the selection was dropped.
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            self.state.select(None);
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -909,11 +7273,49 @@ impl<T> StatefulList<T> {
                     i - 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
 
+    /// Replaces the items, moving the selection up to the last item if the
+    /// list got shorter than the selected index. A list that was empty
+    /// starts out on its first item.
+    fn set_items(&mut self, items: Vec<T>) {
+        let was_empty = self.items.is_empty();
+        self.items = items;
+        match self.state.selected() {
+            Some(selected) => self.select_nearest(selected),
+            None if was_empty => self.select_nearest(0),
+            None => {}
+        }
+    }
+
+    fn selected_item(&self) -> Option<&T> {
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+
+    fn select_nearest(&mut self, index: usize) {
+        if self.items.is_empty() {
+            self.state.select(None);
+        } else {
+            self.state.select(Some(index.min(self.items.len() - 1)));
+        }
+    }
+
+    fn sort_keeping_selection<K: PartialEq>(
+        &mut self,
+        key: impl Fn(&T) -> K,
+        compare: impl Fn(&T, &T) -> Ordering,
+    ) {
+        let selected = self.selected_item().map(&key);
+        self.items.sort_by(compare);
+        if let Some(selected) = selected {
+            let index = self.items.iter().position(|item| key(item) == selected);
+            self.state.select(index);
+        }
+    }
+
     fn unselect(&mut self) {
         let offset = self.state.offset();
         self.last_selected = self.state.selected();
@@ -921,3 +7323,912 @@ impl<T> StatefulList<T> {
         *self.state.offset_mut() = offset;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_keys_move_at_most_once_per_interval() {
+        let mut repeat = KeyRepeat::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(repeat.accept(KeyCode::Down, at(0)));
+        assert!(!repeat.accept(KeyCode::Down, at(5)));
+        assert!(!repeat.accept(KeyCode::Down, at(35)));
+        assert!(repeat.accept(KeyCode::Down, at(40)));
+        // Changing direction is never held back.
+        assert!(repeat.accept(KeyCode::Up, at(41)));
+    }
+
+    #[test]
+    fn required_fields_follow_the_schema_including_inline_anträge() {
+        let params = vec![
+            Param::new("Titel", "titel", ""),
+            Param::new("Inhalt", "inhalt", ""),
+            Param::new("Antrag 1: Titel", "antrag.1.titel", " "),
+            Param::new("Antrag 1: Antragstext", "antrag.1.antragstext", ""),
+        ];
+        assert_eq!(
+            missing_required(&SelectedLayout::Tops, &params),
+            vec!["Titel", "Antrag 1: Titel"]
+        );
+        assert!(missing_required(&SelectedLayout::Anträge, &params[1..2]).is_empty());
+        assert!(is_required(&SelectedLayout::Sitzungen, "datum"));
+    }
+
+    #[test]
+    fn prefix_match_finds_the_first_name_ignoring_case() {
+        let names = ["Finanzen", "Begrüßung", "Fachschaftsfahrt", "Ältestenrat"];
+        assert_eq!(prefix_match(names.into_iter(), "f"), Some(0));
+        assert_eq!(prefix_match(names.into_iter(), "fa"), Some(2));
+        assert_eq!(prefix_match(names.into_iter(), "BEGR"), Some(1));
+        assert_eq!(prefix_match(names.into_iter(), "ält"), Some(3));
+        assert_eq!(prefix_match(names.into_iter(), "x"), None);
+        assert_eq!(prefix_match(names.into_iter(), ""), None);
+    }
+
+    #[test]
+    fn agenda_line_numbers_tops_by_weight() {
+        let top = |name: &str, weight| Top {
+            name: name.to_string(),
+            weight,
+            ..Top::default()
+        };
+        let tops = [
+            top("Verschiedenes", 30),
+            top("Begrüßung", 10),
+            top("Finanzen", 20),
+        ];
+        assert_eq!(
+            agenda_line(&tops),
+            "1. Begrüßung · 2. Finanzen · 3. Verschiedenes"
+        );
+    }
+
+    #[test]
+    fn protocol_lists_tops_with_their_anträge() {
+        let sitzung = Sitzung {
+            name: "Sitzung 3".to_string(),
+            datum: "2024-05-02T18:00:00".parse().unwrap(),
+            ..Sitzung::default()
+        };
+        let top = Top {
+            name: "Finanzen".to_string(),
+            protokoll: "Beschlossen.".to_string(),
+            ..Top::default()
+        };
+        let antrag = Antrag {
+            titel: "Kaffee".to_string(),
+            antragstext: "Der FSR möge\nKaffee kaufen.".to_string(),
+            ..Antrag::default()
+        };
+        let text: Vec<String> = protocol_lines(&sitzung, &[(top, vec![antrag])])
+            .iter()
+            .map(Line::to_string)
+            .collect();
+        assert_eq!(
+            text,
+            [
+                "Sitzung 3",
+                "2024-05-02 18:00",
+                "",
+                "Top 1: Finanzen",
+                "",
+                "Kaffee (eingereicht)",
+                "Antragstext",
+                "Der FSR möge",
+                "Kaffee kaufen.",
+                "",
+                "Protokoll",
+                "Beschlossen.",
+            ]
+        );
+    }
+
+    #[test]
+    fn jumps_between_invalid_fields_wrapping_around() {
+        let params = vec![
+            Param::new("Name", "name", ""),
+            Param::new("Datum", "datum", "irgendwann"),
+            Param::new("Ort", "ort", ""),
+            Param::new("Datum", "datum", "2024-05-02 18:00:00"),
+        ];
+        let layout = SelectedLayout::Sitzungen;
+        assert_eq!(next_invalid(&layout, &params, None, true), Some(0));
+        assert_eq!(next_invalid(&layout, &params, None, false), Some(1));
+        assert_eq!(next_invalid(&layout, &params, Some(0), true), Some(1));
+        assert_eq!(next_invalid(&layout, &params, Some(1), true), Some(0));
+        assert_eq!(next_invalid(&layout, &params, Some(0), false), Some(1));
+        assert_eq!(next_invalid(&layout, &params[2..], Some(0), true), None);
+    }
+
+    #[test]
+    fn the_scrollbar_counts_lines_not_rows() {
+        let heights = [2, 1, 3, 1];
+        assert_eq!(scroll_rows(&heights, 0), (7, 0));
+        assert_eq!(scroll_rows(&heights, 2), (7, 3));
+        assert_eq!(scroll_rows(&heights, 9), (7, 7));
+        assert_eq!(scroll_rows(&[], 0), (0, 0));
+    }
+
+    #[test]
+    fn row_area_skips_the_rows_above_the_selected_one() {
+        let area = Rect::new(0, 2, 30, 5);
+        let heights = [2, 1, 2, 1, 1];
+        assert_eq!(row_area(area, &heights, 0, 2), Some(Rect::new(0, 5, 30, 1)));
+        assert_eq!(row_area(area, &heights, 2, 3), Some(Rect::new(0, 4, 30, 1)));
+        assert_eq!(row_area(area, &heights, 0, 4), None);
+        assert_eq!(row_area(area, &heights, 3, 1), None);
+    }
+
+    #[test]
+    fn session_label_counts_down_in_minutes_then_seconds() {
+        assert_eq!(
+            session_label(Duration::from_secs(600), Lang::En),
+            "session expires in 10m"
+        );
+        assert_eq!(
+            session_label(Duration::from_secs(61), Lang::En),
+            "session expires in 2m"
+        );
+        assert_eq!(
+            session_label(Duration::from_secs(42), Lang::En),
+            "session expires in 42s"
+        );
+        assert_eq!(session_label(Duration::ZERO, Lang::En), "session expired");
+        assert_eq!(
+            session_label(Duration::from_secs(42), Lang::De),
+            "Anmeldung läuft in 42s ab"
+        );
+    }
+
+    #[test]
+    fn backend_versions_outside_the_tested_range_are_flagged() {
+        assert_eq!(parse_version("v1.4"), Some((1, 4, 0)));
+        assert_eq!(parse_version("1.2.3-beta"), Some((1, 2, 3)));
+        assert_eq!(parse_version("latest"), None);
+        assert_eq!(version_warning("1.9.9"), None);
+        assert!(version_warning("2.0.0").is_some());
+        assert!(version_warning("0.9").is_some());
+        assert!(version_warning("nightly").is_some());
+    }
+
+    #[test]
+    fn list_response_accepts_bare_array() {
+        let response: ListResponse<u32> = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(response.into_items(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn list_response_accepts_paged_wrapper() {
+        let response: ListResponse<u32> =
+            serde_json::from_str(r#"{ "results": [1, 2], "count": 5 }"#).unwrap();
+        assert_eq!(response.into_items(), vec![1, 2]);
+    }
+
+    #[test]
+    fn typ_abbreviation_is_always_three_columns() {
+        assert_eq!(typ_abbreviation(Some("Finanzantrag")), "FIN");
+        assert_eq!(typ_abbreviation(Some("Satzungsänderung")), "SAT");
+        assert_eq!(typ_abbreviation(Some("GO")), "GO ");
+        assert_eq!(typ_abbreviation(Some("  ")), " — ");
+        assert_eq!(typ_abbreviation(None), " — ");
+    }
+
+    #[test]
+    fn fuzzy_rank_keeps_matches_best_first() {
+        let names = vec!["Finanzen", "Sonstiges", "Fachschaftsfahrt", "Begrüßung"];
+        let ranked = fuzzy_rank(names.clone(), "fsf", |name| name);
+        assert_eq!(ranked, vec!["Fachschaftsfahrt"]);
+        let ranked = fuzzy_rank(names.clone(), "fin", |name| name);
+        assert_eq!(ranked[0], "Finanzen");
+        assert_eq!(fuzzy_rank(names.clone(), "", |name| name), names);
+    }
+
+    #[test]
+    fn renumbering_only_touches_out_of_place_weights() {
+        let tops: Vec<Top> = [0, 15, 1000, 3]
+            .into_iter()
+            .map(|weight| Top {
+                id: Uuid::new_v4(),
+                weight,
+                ..Top::default()
+            })
+            .collect();
+        assert_eq!(
+            renumbered_weights(&tops),
+            vec![(tops[1].id, 1), (tops[2].id, 2)]
+        );
+    }
+
+    #[test]
+    fn timestamps_are_shown_in_the_given_time_zone() {
+        let berlin = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            format_timestamp("2024-05-02T16:30:00Z", &berlin),
+            "2024-05-02 18:30"
+        );
+        assert_eq!(
+            format_timestamp("2024-05-02T16:30:00.123456", &berlin),
+            "2024-05-02 18:30"
+        );
+        assert_eq!(format_timestamp("gestern", &berlin), "gestern");
+    }
+
+    #[test]
+    fn collapsed_sections_show_only_their_heading() {
+        let antrag = Antrag {
+            begründung: "eins\nzwei\ndrei".to_string(),
+            antragstext: "text".to_string(),
+            ..Antrag::default()
+        };
+        let (expanded, headings) = antrag_detail_lines(&antrag, &Collapsed::default());
+        assert_eq!(section_at(&headings, 0), Some(Section::Metadaten));
+        let begründung = headings[1].0;
+        assert_eq!(
+            section_at(&headings, begründung + 2),
+            Some(Section::Begründung)
+        );
+
+        let mut collapsed = Collapsed::default();
+        *collapsed.get_mut(Section::Begründung) = true;
+        let (lines, _) = antrag_detail_lines(&antrag, &collapsed);
+        assert_eq!(lines.len(), expanded.len() - 3);
+
+        collapsed.toggle_all();
+        assert!(collapsed.get(Section::Antragstext));
+        collapsed.toggle_all();
+        assert!(!collapsed.get(Section::Begründung));
+    }
+
+    #[test]
+    fn attendance_roster_keeps_guests_who_were_present() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let roster = attendance_roster(&names(&["Alex", "Kim"]), &names(&["Kim", "Gast"]));
+        assert_eq!(
+            roster,
+            vec![
+                ("Alex".to_string(), false),
+                ("Kim".to_string(), true),
+                ("Gast".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn template_placeholders_are_filled_in() {
+        let sitzung = Sitzung {
+            name: "Sitzung 3".to_string(),
+            datum: NaiveDateTime::parse_from_str("2024-05-02 18:00", "%Y-%m-%d %H:%M").unwrap(),
+            ..Sitzung::default()
+        };
+        let top = Top {
+            name: "Finanzen".to_string(),
+            ..Top::default()
+        };
+        assert_eq!(
+            fill_template(
+                "{sitzung} am {datum}, {top}: Der FSR möge beschließen: …",
+                &sitzung,
+                &top
+            ),
+            "Sitzung 3 am 2024-05-02, Finanzen: Der FSR möge beschließen: …"
+        );
+    }
+
+    #[test]
+    fn history_drops_forward_places_on_a_new_one() {
+        let mut history = History::default();
+        history.push(1);
+        history.push(2);
+        assert_eq!(history.back(3), Some(2));
+        assert_eq!(history.back(2), Some(1));
+        assert_eq!(history.back(1), None);
+        assert_eq!(history.forward(1), Some(2));
+        history.push(2);
+        assert_eq!(history.forward(4), None);
+        assert_eq!(history.back(4), Some(2));
+    }
+
+    #[test]
+    fn upcoming_sitzungen_start_today() {
+        let at = |datum: &str| Sitzung {
+            datum: NaiveDateTime::parse_from_str(datum, "%Y-%m-%d %H:%M").unwrap(),
+            ..Sitzung::default()
+        };
+        let now = NaiveDateTime::parse_from_str("2024-05-02 20:00", "%Y-%m-%d %H:%M").unwrap();
+        let sitzungen = [
+            at("2024-05-09 18:00"),
+            at("2024-04-25 18:00"),
+            at("2024-05-02 18:00"),
+        ];
+        let upcoming: Vec<_> = upcoming_sitzungen(&sitzungen, now)
+            .iter()
+            .map(|s| s.datum)
+            .collect();
+        assert_eq!(upcoming, vec![sitzungen[2].datum, sitzungen[0].datum]);
+    }
+
+    #[test]
+    fn the_next_sitzung_comes_first() {
+        let at = |datum: &str| Sitzung {
+            datum: NaiveDateTime::parse_from_str(datum, "%Y-%m-%d %H:%M").unwrap(),
+            ..Sitzung::default()
+        };
+        let now = NaiveDateTime::parse_from_str("2024-05-02 20:00", "%Y-%m-%d %H:%M").unwrap();
+        let mut sitzungen = [
+            at("2024-05-16 18:00"),
+            at("2024-04-25 18:00"),
+            at("2024-05-09 18:00"),
+        ];
+        let sort = Sort {
+            key: SortKey::Datum,
+            descending: true,
+        };
+        sitzungen.sort_by(compare_sitzungen(sort, now, &HashSet::new()));
+        let order: Vec<_> = sitzungen
+            .iter()
+            .map(|s| s.datum.format("%m-%d").to_string())
+            .collect();
+        assert_eq!(order, ["05-09", "05-16", "04-25"]);
+    }
+
+    #[test]
+    fn finds_anträge_by_author_across_tops() {
+        let antrag = |titel: &str, author: Option<&str>| Antrag {
+            titel: titel.to_string(),
+            author: author.map(str::to_string),
+            ..Antrag::default()
+        };
+        let top = |name: &str| Top {
+            name: name.to_string(),
+            ..Top::default()
+        };
+        let agenda = vec![
+            (
+                top("Finanzen"),
+                vec![antrag("Kaffee", Some("Kim Meyer")), antrag("Tee", None)],
+            ),
+            (
+                top("Sonstiges"),
+                vec![antrag("Sofa", Some("kim")), antrag("Party", Some("Alex"))],
+            ),
+        ];
+        let found: Vec<_> = anträge_by_author(agenda, "KIM")
+            .into_iter()
+            .map(|(top, antrag)| (top.name, antrag.titel))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Finanzen".to_string(), "Kaffee".to_string()),
+                ("Sonstiges".to_string(), "Sofa".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn agenda_label_shows_position_and_percentage() {
+        assert_eq!(agenda_label(4, 11, Lang::En), "Top 4 of 11 — 36%");
+        assert_eq!(agenda_label(1, 1, Lang::En), "Top 1 of 1 — 100%");
+        assert_eq!(agenda_label(4, 11, Lang::De), "Top 4 von 11 — 36%");
+    }
+
+    #[test]
+    fn breadcrumb_names_sitzung_top_and_antrag() {
+        let sitzung = Sitzung {
+            name: "Sitzung 3".to_string(),
+            datum: NaiveDateTime::parse_from_str("2024-05-02 18:00", "%Y-%m-%d %H:%M").unwrap(),
+            ..Sitzung::default()
+        };
+        let top = Top {
+            name: "Finanzen".to_string(),
+            ..Top::default()
+        };
+        assert_eq!(
+            breadcrumb(&sitzung, &top, "Neue Kaffeemaschine"),
+            "Sitzung 3 (2024-05-02) › Finanzen › Neue Kaffeemaschine"
+        );
+    }
+
+    #[test]
+    fn finds_todays_or_the_next_sitzung() {
+        let at = |datum: &str| Sitzung {
+            datum: NaiveDateTime::parse_from_str(datum, "%Y-%m-%d %H:%M").unwrap(),
+            ..Sitzung::default()
+        };
+        let now = NaiveDateTime::parse_from_str("2024-05-02 20:00", "%Y-%m-%d %H:%M").unwrap();
+        let sitzungen = [
+            at("2024-05-09 18:00"),
+            at("2024-05-02 18:00"),
+            at("2024-05-06 18:00"),
+        ];
+        assert_eq!(find_todays_sitzung(&sitzungen, now), Some((1, true)));
+        assert_eq!(
+            find_todays_sitzung(&[sitzungen[0].clone(), sitzungen[2].clone()], now),
+            Some((1, false))
+        );
+        assert_eq!(find_todays_sitzung(&[], now), None);
+    }
+
+    #[test]
+    fn inhalt_round_trips_through_edit_for_every_json_type() {
+        use serde_json::json;
+        for inhalt in [
+            json!(null),
+            json!(true),
+            json!(123),
+            json!(1.5),
+            json!("text"),
+            json!([1, "zwei"]),
+            json!({ "a": { "b": [] } }),
+        ] {
+            let text = inhalt_to_text(&inhalt);
+            assert_eq!(inhalt_from_text(&text, &inhalt), inhalt, "{}", text);
+        }
+        assert_eq!(inhalt_to_text(&json!(null)), "");
+        assert_eq!(inhalt_to_text(&json!("text")), "text");
+    }
+
+    #[test]
+    fn edited_inhalt_that_no_longer_fits_its_type_becomes_a_string() {
+        use serde_json::json;
+        assert_eq!(inhalt_from_text("", &json!(null)), json!(null));
+        assert_eq!(inhalt_from_text("neu", &json!(null)), json!("neu"));
+        assert_eq!(inhalt_from_text("42", &json!(7)), json!(42));
+        assert_eq!(inhalt_from_text("viele", &json!(7)), json!("viele"));
+        assert_eq!(inhalt_from_text("42", &json!("7")), json!("42"));
+        assert_eq!(inhalt_from_text("[1", &json!([])), json!("[1"));
+    }
+
+    #[test]
+    fn malformed_items_are_skipped_individually() {
+        let response: ListResponse<serde_json::Value> = serde_json::from_str(
+            r#"[
+                { "id": "67e55044-10b1-426f-9247-bb680e5fe0c8", "name": "a", "weight": 1, "extra": true },
+                { "id": "not a uuid", "name": "b" },
+                { "id": "67e55044-10b1-426f-9247-bb680e5fe0c9" }
+            ]"#,
+        )
+        .unwrap();
+        let (tops, failed): (Vec<Top>, usize) = response.parse_items();
+        assert_eq!(failed, 1);
+        assert_eq!(tops.len(), 2);
+        assert_eq!(tops[0].name, "a");
+        assert_eq!(tops[1].weight, 0);
+    }
+
+    #[test]
+    fn next_and_previous_on_empty_list_select_nothing() {
+        let mut list: StatefulList<u32> = StatefulList::with_items(vec![]);
+        list.next();
+        assert_eq!(list.state.selected(), None);
+        list.previous();
+        assert_eq!(list.state.selected(), None);
+    }
+
+    #[test]
+    fn shrinking_the_items_keeps_the_selection_in_range() {
+        let mut list = StatefulList::with_items(vec![1, 2, 3, 4]);
+        list.state.select(Some(3));
+        list.set_items(vec![1, 2]);
+        assert_eq!(list.state.selected(), Some(1));
+        assert_eq!(list.selected_item(), Some(&2));
+        list.set_items(vec![]);
+        assert_eq!(list.state.selected(), None);
+        list.set_items(vec![5]);
+        assert_eq!(list.state.selected(), Some(0));
+        assert_eq!(
+            StatefulList::<u32>::with_items(vec![]).state.selected(),
+            None
+        );
+    }
+
+    #[test]
+    fn single_item_list_stays_on_first_item() {
+        let mut list = StatefulList::with_items(vec![1]);
+        list.next();
+        assert_eq!(list.state.selected(), Some(0));
+        list.previous();
+        assert_eq!(list.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn next_and_previous_wrap_around() {
+        let mut list = StatefulList::with_items(vec![1, 2, 3]);
+        list.next();
+        list.next();
+        assert_eq!(list.state.selected(), Some(2));
+        list.next();
+        assert_eq!(list.state.selected(), Some(0));
+        list.previous();
+        assert_eq!(list.state.selected(), Some(2));
+        list.previous();
+        assert_eq!(list.state.selected(), Some(1));
+    }
+
+    fn payload_keys(params: &[Param]) -> Vec<String> {
+        params_payload(params).keys().cloned().collect()
+    }
+
+    #[test]
+    fn sitzung_payload_keys() {
+        assert_eq!(payload_keys(&new_sitzung_params()), ["datum", "name"]);
+        assert_eq!(
+            payload_keys(&sitzung_params(&Sitzung::default())),
+            ["datum", "name"]
+        );
+    }
+
+    #[test]
+    fn top_payload_keys() {
+        assert_eq!(
+            payload_keys(&new_top_params()),
+            ["inhalt", "titel", "verantwortlich"]
+        );
+        assert_eq!(
+            payload_keys(&top_params(&Top::default())),
+            ["inhalt", "titel", "verantwortlich"]
+        );
+    }
+
+    #[test]
+    fn inhalt_fields_stay_nested_and_reserved_keys_are_kept() {
+        use serde_json::json;
+        let original = json!({"id": "alt", "titel": "Alt"});
+        let mut data = json!({"id": "top-id", "sitzung_id": "sitzung-id"});
+        let params = vec![
+            Param::new("Titel", "titel", "Finanzen"),
+            Param::new("Inhalt", "inhalt", r#"{"id": "neu", "titel": "Neu"}"#),
+            Param::new("Id", "id", "überschrieben"),
+        ];
+        merge_params(&mut data, &params, Some(&original));
+        assert_eq!(
+            data,
+            json!({
+                "id": "top-id",
+                "sitzung_id": "sitzung-id",
+                "titel": "Finanzen",
+                "inhalt": {"id": "neu", "titel": "Neu"},
+            })
+        );
+    }
+
+    #[test]
+    fn antrag_count_counts_only_eingereicht_as_open() {
+        let antrag = |status| Antrag {
+            status,
+            ..Antrag::default()
+        };
+        let anträge = [
+            antrag(AntragStatus::Eingereicht),
+            antrag(AntragStatus::Angenommen),
+            antrag(AntragStatus::Zurückgezogen),
+        ];
+        let count = backend::AntragCount::of(&anträge);
+        assert_eq!(count, backend::AntragCount { total: 3, open: 1 });
+    }
+
+    #[test]
+    fn agenda_link_fills_in_base_and_id() {
+        let id = Uuid::nil();
+        assert_eq!(
+            agenda_link(AGENDA_URL, URL, id),
+            format!("https://new.hhu-fscs.de/topmanager/sitzung/{}/", id)
+        );
+        assert_eq!(
+            agenda_link("{base}/s?id={id}", "https://x/", id),
+            format!("https://x/s?id={}", id)
+        );
+    }
+
+    #[test]
+    fn clearing_verantwortlich_sends_null() {
+        let top = Top {
+            verantwortlich: Some("Kim".to_string()),
+            ..Top::default()
+        };
+        let mut params = top_params(&top);
+        assert_eq!(params_payload(&params)["verantwortlich"], "Kim");
+        params[2].text = " ".to_string();
+        assert!(params_payload(&params)["verantwortlich"].is_null());
+    }
+
+    #[test]
+    fn a_save_is_only_answered_by_its_own_patch() {
+        let id = Uuid::new_v4();
+        let saving = Saving {
+            url: "https://x/api/topmanager/top/".to_string(),
+            id,
+            params: vec![],
+        };
+        let mutation = |method, id: Uuid| Mutation {
+            method,
+            url: "https://x/api/topmanager/top/".to_string(),
+            body: Some(serde_json::json!({ "id": id.to_string() })),
+            token: String::new(),
+            reload: Reload::All,
+        };
+        assert!(saving.is(&mutation(Method::PATCH, id)));
+        assert!(!saving.is(&mutation(Method::PATCH, Uuid::new_v4())));
+        assert!(!saving.is(&mutation(Method::DELETE, id)));
+    }
+
+    #[test]
+    fn removing_an_inline_antrag_renumbers_the_later_ones() {
+        let mut params = vec![Param::new("Name", "name", "Finanzen")];
+        for number in 1..=3 {
+            for key in ["titel", "begründung"] {
+                params.push(Param::new(
+                    &format!("Antrag {}: {}", number, key),
+                    &format!("antrag.{}.{}", number, key),
+                    format!("{} {}", key, number),
+                ));
+            }
+        }
+        assert!(!remove_inline_antrag(&mut params, 0));
+        assert!(remove_inline_antrag(&mut params, 4));
+        let keys: Vec<_> = params.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "name",
+                "antrag.1.titel",
+                "antrag.1.begründung",
+                "antrag.2.titel",
+                "antrag.2.begründung"
+            ]
+        );
+        assert_eq!(params[3].titel, "Antrag 2: titel");
+        assert_eq!(params[3].text, "titel 3");
+        assert!(missing_required(&SelectedLayout::Tops, &params).is_empty());
+        assert_eq!(inline_antrag_payloads(&params).len(), 2);
+    }
+
+    #[test]
+    fn only_web_links_are_opened() {
+        assert!(web_link("https://example.org/antrag.pdf").is_some());
+        assert!(web_link(" http://example.org/ ").is_some());
+        assert!(web_link("file:///etc/passwd").is_none());
+        assert!(web_link("javascript:alert(1)").is_none());
+        assert!(web_link("antrag.pdf").is_none());
+    }
+
+    #[test]
+    fn unreadable_entries_are_not_counted_for_the_import() {
+        let entwurf = |titel: &str| import::Entwurf {
+            titel: titel.to_string(),
+            begründung: String::new(),
+            antragstext: String::new(),
+        };
+        let (readable, unreadable) = split_unreadable(vec![
+            Ok(entwurf("Eins")),
+            Err("kein Titel".to_string()),
+            Ok(entwurf("Drei")),
+        ]);
+        assert_eq!(
+            readable
+                .iter()
+                .map(|e| e.titel.as_str())
+                .collect::<Vec<_>>(),
+            ["Eins", "Drei"]
+        );
+        assert_eq!(unreadable, ["Eintrag 2: kein Titel"]);
+    }
+
+    #[test]
+    fn antrag_payload_keys() {
+        assert_eq!(
+            payload_keys(&new_antrag_params()),
+            [
+                "anhänge",
+                "antragssteller",
+                "antragstext",
+                "begründung",
+                "titel"
+            ]
+        );
+        assert_eq!(
+            payload_keys(&antrag_params(&Antrag::default())),
+            ["anhänge", "antragstext", "begründung", "titel"]
+        );
+    }
+
+    #[test]
+    fn antrag_payload_values() {
+        let antrag = Antrag {
+            titel: "Titel".to_string(),
+            begründung: "Weil".to_string(),
+            antragstext: "Text".to_string(),
+            anhänge: vec!["https://a".to_string(), "https://b".to_string()],
+            ..Antrag::default()
+        };
+        assert_eq!(
+            serde_json::Value::Object(params_payload(&antrag_params(&antrag))),
+            serde_json::json!({
+                "titel": "Titel",
+                "begründung": "Weil",
+                "antragstext": "Text",
+                "anhänge": ["https://a", "https://b"],
+            })
+        );
+    }
+
+    #[test]
+    fn edited_items_are_sent_with_their_ids_and_version() {
+        let sitzung = Sitzung::default();
+        let antrag = Antrag {
+            id: Uuid::new_v4(),
+            titel: "Alt".to_string(),
+            ..Antrag::default()
+        };
+        let mut params = antrag_params(&antrag);
+        params[0].text = "Neu".to_string();
+        let data = edit_payload(
+            Some(&SelectedLayout::Anträge),
+            &sitzung,
+            None,
+            &antrag,
+            &params,
+            Some("v2"),
+        );
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "id": antrag.id.to_string(),
+                "status": "eingereicht",
+                "titel": "Neu",
+                "begründung": "",
+                "antragstext": "",
+                "anhänge": [],
+                "updated_at": "v2",
+            })
+        );
+
+        let top = Top {
+            id: Uuid::new_v4(),
+            name: "Finanzen".to_string(),
+            ..Top::default()
+        };
+        let data = edit_payload(
+            Some(&SelectedLayout::Tops),
+            &sitzung,
+            Some(&top),
+            &antrag,
+            &top_params(&top),
+            None,
+        );
+        assert_eq!(data["id"], top.id.to_string());
+        assert_eq!(data["sitzung_id"], sitzung.id.to_string());
+        assert_eq!(data["titel"], "Finanzen");
+        assert!(data.get("updated_at").is_none());
+    }
+
+    #[test]
+    fn inline_anträge_are_split_from_the_top_payload() {
+        let mut params = new_top_params();
+        params.push(Param::new("Antrag 1: Titel", "antrag.1.titel", "Erster"));
+        params.push(Param::new("Antrag 2: Titel", "antrag.2.titel", "Zweiter"));
+        assert_eq!(payload_keys(&params), ["inhalt", "titel", "verantwortlich"]);
+        let anträge = inline_antrag_payloads(&params);
+        assert_eq!(anträge.len(), 2);
+        assert_eq!(anträge[1]["titel"], "Zweiter");
+    }
+
+    #[test]
+    fn diff_lines_only_lists_changed_params() {
+        let original = vec![
+            Param::new("Titel", "titel", "Alt"),
+            Param::new("Antragstext", "antragstext", "eins\nzwei"),
+        ];
+        let mut current = original.clone();
+        current[1].text = "eins\ndrei".to_string();
+        let text: Vec<String> = diff_lines(&original, &current)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(text, ["Antragstext", "  eins", "- zwei", "+ drei", ""]);
+        assert_eq!(
+            diff_lines(&original, &original)[0].to_string(),
+            "No changes"
+        );
+    }
+
+    #[test]
+    fn fit_to_width_truncates_with_ellipsis() {
+        assert_eq!(fit_to_width("kurz", 10, false), ["kurz"]);
+        assert_eq!(fit_to_width("Haushaltsplan", 6, false), ["Haush…"]);
+    }
+
+    #[test]
+    fn fit_to_width_counts_display_columns() {
+        assert_eq!(
+            fit_to_width("Größenänderung", 14, false),
+            ["Größenänderung"]
+        );
+        assert_eq!(fit_to_width("Größenänderung", 6, false), ["Größe…"]);
+        assert_eq!(fit_to_width("Fußball 🎉 heute", 11, false), ["Fußball 🎉…"]);
+        assert_eq!(fit_to_width("Fußball 🎉 heute", 10, false), ["Fußball …"]);
+        assert_eq!(fit_to_width("🎉🎉🎉", 4, true), ["🎉🎉", "🎉"]);
+        assert_eq!(fit_to_width("a\u{308}b\u{308}c", 2, false), ["a\u{308}…"]);
+    }
+
+    #[test]
+    fn antrag_table_columns_follow_the_config() {
+        use AntragColumn::*;
+        let mut table = config::AntragTable::default();
+        let undated = [Antrag::default()];
+        let dated = [Antrag {
+            created_at: Some("2024-05-02T18:00:00Z".to_string()),
+            ..Antrag::default()
+        }];
+        assert_eq!(
+            antrag_columns(&table, &undated),
+            [
+                (Titel, None),
+                (Antragssteller, Some(20)),
+                (Status, Some(15))
+            ]
+        );
+        table.hide = vec![Titel, Status];
+        table.widths.insert(Antragssteller, 30);
+        assert_eq!(
+            antrag_columns(&table, &dated),
+            [
+                (Titel, None),
+                (Antragssteller, Some(30)),
+                (Erstellt, Some(10))
+            ]
+        );
+    }
+
+    #[test]
+    fn typ_badge_is_three_columns_wide() {
+        assert_eq!(typ_abbreviation(Some("Änderung")), "ÄND");
+        assert_eq!(typ_abbreviation(Some("ß")), "SS ");
+        assert_eq!(typ_abbreviation(Some("🎉Fest")).width(), 3);
+        assert_eq!(typ_abbreviation(None).width(), 3);
+    }
+
+    #[test]
+    fn fit_to_width_wraps_at_spaces() {
+        assert_eq!(
+            fit_to_width("Antrag auf Finanzierung", 10, true),
+            ["Antrag auf", "Finanzieru", "ng"]
+        );
+    }
+
+    #[test]
+    fn sort_keeps_the_selected_item_selected() {
+        let mut list = StatefulList::with_items(vec![3, 1, 2]);
+        list.sort_keeping_selection(|n| *n, |a, b| a.cmp(b));
+        assert_eq!(list.items, vec![1, 2, 3]);
+        assert_eq!(list.selected_item(), Some(&3));
+    }
+
+    #[test]
+    fn unselect_keeps_offset_and_remembers_selection() {
+        let mut list = StatefulList::with_items(vec![1, 2, 3, 4]);
+        list.next();
+        *list.state.offset_mut() = 1;
+        list.unselect();
+        assert_eq!(list.state.selected(), None);
+        assert_eq!(list.state.offset(), 1);
+        list.next();
+        assert_eq!(list.state.selected(), Some(1));
+
+        // A refresh that returns fewer items than the remembered index.
+        let mut list = StatefulList::with_items(vec![1, 2, 3, 4]);
+        list.state.select(Some(3));
+        list.unselect();
+        list.set_items(vec![1, 2]);
+        list.next();
+        assert_eq!(list.state.selected(), Some(1));
+        assert_eq!(list.selected_item(), Some(&2));
+        list.unselect();
+        list.set_items(vec![1]);
+        list.previous();
+        assert_eq!(list.selected_item(), Some(&1));
+    }
+}
@@ -0,0 +1,247 @@
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    Point,
+};
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{self, PathBuf};
+
+use crate::{Antrag, Sitzung, Top};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 25.0;
+/// Characters of body text that fit between the margins. The built-in
+/// fonts come without metrics, so this assumes an average Helvetica glyph.
+const COLUMNS: usize = 82;
+
+/// Where `antrag` is written, `antrag-<titel>.pdf` with everything that
+/// does not belong in a file name replaced.
+pub fn file_name(antrag: &Antrag) -> String {
+    let titel: String = antrag
+        .titel
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_whitespace() || c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    match titel.as_str() {
+        "" => format!("antrag-{}.pdf", antrag.id),
+        titel => format!("antrag-{}.pdf", titel),
+    }
+}
+
+/// `name` with `-<n>` before its extension, from the second try on.
+fn numbered(name: &str, n: usize) -> String {
+    match (n, name.rsplit_once('.')) {
+        (1, _) => name.to_string(),
+        (_, Some((stem, extension))) => format!("{}-{}.{}", stem, n, extension),
+        (_, None) => format!("{}-{}", name, n),
+    }
+}
+
+/// Writes `bytes` to `name` in the current directory, or to `name-2`,
+/// `name-3`, … if that exists already, and returns the absolute path.
+/// An existing file is never replaced.
+pub fn write_new(name: &str, bytes: &[u8]) -> io::Result<PathBuf> {
+    for n in 1.. {
+        let path = PathBuf::from(numbered(name, n));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(bytes)?;
+                return path::absolute(&path);
+            }
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!()
+}
+
+/// `antrag` as a formal submission, headed by `header` and where it is
+/// on the agenda.
+pub fn antrag(
+    antrag: &Antrag,
+    sitzung: &Sitzung,
+    top: &Top,
+    header: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
+    let (doc, page, layer) =
+        PdfDocument::new(&antrag.titel, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Antrag");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+    let layer = doc.get_page(page).get_layer(layer);
+    let mut writer = Writer {
+        doc: &doc,
+        layer,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    if let Some(header) = header.filter(|header| !header.trim().is_empty()) {
+        writer.line(header.trim(), 12.0, &bold);
+    }
+    writer.line(
+        &format!(
+            "{}, {}",
+            sitzung.name,
+            sitzung.datum.format("%d.%m.%Y %H:%M")
+        ),
+        10.0,
+        &regular,
+    );
+    writer.line(&format!("TOP: {}", top.name), 10.0, &regular);
+    writer.rule();
+
+    writer.space(4.0);
+    for line in wrap(&antrag.titel, COLUMNS * 11 / 16) {
+        writer.line(&line, 16.0, &bold);
+    }
+    if let Some(typ) = antrag.typ.as_deref().filter(|typ| !typ.is_empty()) {
+        writer.line(typ, 10.0, &regular);
+    }
+    if let Some(author) = antrag.author.as_deref().filter(|a| !a.is_empty()) {
+        writer.line(&format!("Antragssteller: {}", author), 11.0, &regular);
+    }
+    writer.section("Begründung", &antrag.begründung, &regular, &bold);
+    writer.section("Antragstext", &antrag.antragstext, &regular, &bold);
+
+    Ok(doc.save_to_bytes()?)
+}
+
+/// Writes lines from the top of the page down, starting a new page when
+/// the bottom margin is reached.
+struct Writer<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl Writer<'_> {
+    fn line(&mut self, text: &str, size: f32, font: &IndirectFontRef) {
+        let height = size * 0.45;
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Antrag");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= height;
+        self.layer
+            .use_text(text, size, Mm(MARGIN), Mm(self.y), font);
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn rule(&mut self) {
+        self.space(2.0);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn section(
+        &mut self,
+        title: &str,
+        text: &str,
+        regular: &IndirectFontRef,
+        bold: &IndirectFontRef,
+    ) {
+        if text.trim().is_empty() {
+            return;
+        }
+        self.space(6.0);
+        self.line(title, 12.0, bold);
+        self.space(1.0);
+        for line in wrap(text.trim(), COLUMNS) {
+            self.line(&line, 11.0, regular);
+        }
+    }
+}
+
+/// Breaks `text` into lines of at most `columns` characters at spaces,
+/// keeping its own line breaks. Longer words are split.
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines = vec![];
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word = word.to_string();
+            while word.chars().count() > columns {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let split = word
+                    .char_indices()
+                    .nth(columns)
+                    .map_or(word.len(), |(i, _)| i);
+                lines.push(word[..split].to_string());
+                word = word[split..].to_string();
+            }
+            if line.is_empty() {
+                line = word;
+            } else if line.chars().count() + 1 + word.chars().count() <= columns {
+                line.push(' ');
+                line.push_str(&word);
+            } else {
+                lines.push(std::mem::replace(&mut line, word));
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_spaces_and_keeps_paragraphs() {
+        assert_eq!(
+            wrap("Der FSR möge beschließen\n\nund zwar sofort", 12),
+            ["Der FSR möge", "beschließen", "", "und zwar", "sofort"]
+        );
+        assert_eq!(wrap("abcdefghij xy", 4), ["abcd", "efgh", "ij", "xy"]);
+    }
+
+    #[test]
+    fn file_name_has_no_path_separators() {
+        let antrag = Antrag {
+            titel: " Mittel für 2024/25: Party ".to_string(),
+            ..Antrag::default()
+        };
+        assert_eq!(file_name(&antrag), "antrag-Mittel-für-2024-25--Party.pdf");
+    }
+
+    #[test]
+    fn later_files_get_a_number() {
+        assert_eq!(numbered("antrag-Party.pdf", 1), "antrag-Party.pdf");
+        assert_eq!(numbered("antrag-Party.pdf", 2), "antrag-Party-2.pdf");
+        assert_eq!(numbered("antrag", 3), "antrag-3");
+    }
+
+    #[test]
+    fn long_text_continues_on_a_new_page() {
+        let pages = |antragstext: &str| {
+            let antrag = Antrag {
+                titel: "Haushalt".to_string(),
+                antragstext: antragstext.to_string(),
+                ..Antrag::default()
+            };
+            let top = Top::default();
+            let pdf = super::antrag(&antrag, &Sitzung::default(), &top, Some("FSR")).unwrap();
+            String::from_utf8_lossy(&pdf).matches("/Type/Page/").count()
+        };
+        assert_eq!(pages("kurz"), 1);
+        assert_eq!(pages(&"Zeile\n".repeat(200)), 5);
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How many visited Sitzungen and Tops are remembered.
+const MAX_RECENT: usize = 10;
+
+/// State the app writes itself, kept apart from the hand-edited config.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Session {
+    pub pinned: HashSet<Uuid>,
+    /// Most recently opened first.
+    pub recent: Vec<Visit>,
+    /// Show long lines of the detail, protocol and preview panes cut off
+    /// instead of wrapped, toggled with `w`.
+    pub no_wrap: bool,
+    /// Show the Anträge as a table with a column per field, toggled with `T`.
+    pub antrag_table: bool,
+}
+
+/// An opened Sitzung, or a Top together with its Sitzung.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Visit {
+    pub sitzung_id: Uuid,
+    pub sitzung: String,
+    pub top: Option<VisitedTop>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct VisitedTop {
+    pub id: Uuid,
+    pub name: String,
+}
+
+impl Visit {
+    pub fn label(&self) -> String {
+        match &self.top {
+            Some(top) => format!("Top: {} ({})", top.name, self.sitzung),
+            None => format!("Sitzung: {}", self.sitzung),
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.top.as_ref().map_or(self.sitzung_id, |top| top.id)
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("sitzungsverwaltung").join("session.toml"))
+}
+
+pub fn load() -> anyhow::Result<Session> {
+    let Some(path) = path().filter(|path| path.exists()) else {
+        return Ok(Session::default());
+    };
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("invalid session file {}", path.display()))
+}
+
+impl Session {
+    /// Moves `visit` to the front of the recent list.
+    pub fn visit(&mut self, visit: Visit) {
+        self.recent.retain(|recent| recent.id() != visit.id());
+        self.recent.insert(0, visit);
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path().context("no data directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("could not create {}", dir.display()))?;
+        }
+        std::fs::write(&path, toml::to_string(self)?)
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_visits_survive_a_round_trip() {
+        let mut session = Session::default();
+        let sitzung = Visit {
+            sitzung_id: Uuid::new_v4(),
+            sitzung: "Sitzung".to_string(),
+            top: None,
+        };
+        let top = Visit {
+            top: Some(VisitedTop {
+                id: Uuid::new_v4(),
+                name: "Top".to_string(),
+            }),
+            ..sitzung.clone()
+        };
+        session.visit(sitzung.clone());
+        session.visit(top.clone());
+        session.visit(sitzung.clone());
+        assert_eq!(session.recent, vec![sitzung.clone(), top.clone()]);
+
+        let text = toml::to_string(&session).unwrap();
+        let loaded: Session = toml::from_str(&text).unwrap();
+        assert_eq!(loaded.recent, vec![sitzung, top]);
+    }
+
+    #[test]
+    fn recent_visits_are_capped() {
+        let mut session = Session::default();
+        for i in 0..MAX_RECENT + 3 {
+            session.visit(Visit {
+                sitzung_id: Uuid::new_v4(),
+                sitzung: i.to_string(),
+                top: None,
+            });
+        }
+        assert_eq!(session.recent.len(), MAX_RECENT);
+        assert_eq!(session.recent[0].sitzung, (MAX_RECENT + 2).to_string());
+    }
+}